@@ -0,0 +1,29 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// The minimal time spent measuring a single function.
+const MEASUREMENT_TIME: Duration = Duration::from_millis(500);
+
+/// Returns the average duration of a single call of the function.
+///
+/// The function is called once before measurement starts (e.g. to initialize lazy statics).
+pub fn measure<T>(mut f: impl FnMut() -> T) -> Duration {
+    black_box(f());
+
+    let start = Instant::now();
+    let mut iterations = 0;
+    while start.elapsed() < MEASUREMENT_TIME {
+        black_box(f());
+        iterations += 1;
+    }
+    start.elapsed() / iterations
+}
+
+/// Measures the function and prints the result.
+pub fn bench<T>(name: &str, f: impl FnMut() -> T) -> Duration {
+    let duration = measure(f);
+    println!("{name:<50} {duration:>12.2?}");
+    duration
+}
@@ -0,0 +1,59 @@
+//! Benchmarks of the IPA prover.
+//!
+//! Compares [IpaProof::open_polynomial] with the straightforward implementation (that doesn't use
+//! precomputed CRS tables and keeps `G` in projective representation) and reports the speedup. That
+//! both create byte-identical proofs is checked by the `same_as_reference` unit test.
+//!
+//! Run with: `cargo bench --bench ipa --features bench`
+
+use portal_verkle_primitives::{
+    proof::{
+        lagrange_basis::LagrangeBasis, transcript::Transcript, IpaProof, MultiProof,
+        ProverMultiQuery,
+    },
+    ScalarField,
+};
+
+mod common;
+
+fn polynomial() -> LagrangeBasis {
+    LagrangeBasis::new(std::array::from_fn(|i| {
+        -ScalarField::from(i as u64 * 7919 + 1)
+    }))
+}
+
+fn main() {
+    let x = ScalarField::from(1234u64);
+    let c = polynomial().commit();
+
+    let open = || {
+        IpaProof::open_polynomial(
+            Some(c.clone()),
+            polynomial(),
+            x.clone(),
+            &mut Transcript::new("bench"),
+        )
+        .unwrap()
+    };
+    let open_reference = || {
+        IpaProof::open_polynomial_reference(polynomial(), x.clone(), &mut Transcript::new("bench"))
+    };
+
+    let duration = common::bench("ipa/open_polynomial", open);
+    let reference_duration = common::bench("ipa/open_polynomial_reference", open_reference);
+    println!(
+        "{:<50} {:>12.2}x",
+        "ipa/speedup",
+        reference_duration.as_secs_f64() / duration.as_secs_f64()
+    );
+
+    // The multiproof with openings of 16 polynomials at 2 points each (similar to the proof of the
+    // branch bundle)
+    common::bench("multiproof/create/16x2", || {
+        let mut queries = ProverMultiQuery::new();
+        for i in 0..16u8 {
+            queries.add_vector(c.clone(), polynomial(), [i, i + 16]);
+        }
+        MultiProof::create(queries, &mut Transcript::new("bench")).unwrap()
+    });
+}
@@ -0,0 +1,88 @@
+//! Benchmarks of the MSM backends.
+//!
+//! Besides measuring every backend, it reports the thresholds at which [Auto] should switch
+//! between algorithms, so they can be compared with [Auto::DENSE_PARALLEL_THRESHOLD] and
+//! [Auto::SPARSE_TO_DENSE_THRESHOLD].
+//!
+//! Run with: `cargo bench --bench msm`
+
+use std::time::Duration;
+
+use portal_verkle_primitives::{
+    constants::VERKLE_NODE_WIDTH, Auto, MsmBackend, ParallelPippenger, ParallelPrecompWnaf,
+    Pippenger, PrecompWnaf, ScalarField,
+};
+
+mod common;
+
+fn scalars<const N: usize>() -> [ScalarField; N] {
+    std::array::from_fn(|i| -ScalarField::from(i as u64 + 1))
+}
+
+/// Measures all backends for full vectors and returns duration of serial and parallel wNAF.
+fn bench_dense<const N: usize>() -> (Duration, Duration) {
+    let scalars = scalars::<N>();
+    let serial = common::bench(&format!("dense/{N}/precomp_wnaf"), || {
+        PrecompWnaf::commit(&scalars)
+    });
+    let parallel = common::bench(&format!("dense/{N}/parallel_precomp_wnaf"), || {
+        ParallelPrecompWnaf::commit(&scalars)
+    });
+    common::bench(&format!("dense/{N}/pippenger"), || {
+        Pippenger::commit(&scalars)
+    });
+    common::bench(&format!("dense/{N}/parallel_pippenger"), || {
+        ParallelPippenger::commit(&scalars)
+    });
+    (serial, parallel)
+}
+
+fn main() {
+    let dense = [
+        (16, bench_dense::<16>()),
+        (32, bench_dense::<32>()),
+        (64, bench_dense::<64>()),
+        (128, bench_dense::<128>()),
+        (256, bench_dense::<256>()),
+    ];
+    // The smallest domain size from which parallel wNAF is faster for all larger domains as well
+    let dense_threshold = dense
+        .iter()
+        .rev()
+        .take_while(|(_, (serial, parallel))| parallel < serial)
+        .last()
+        .map(|(n, _)| *n);
+
+    // Sparse commitment is calculated either with the single scalar multiplications or by
+    // committing to full vector. The Verkle trie CRS is always used.
+    let full = scalars::<VERKLE_NODE_WIDTH>();
+    let dense_duration = common::bench("sparse/dense", || Auto::commit(&full));
+    let mut sparse_threshold = None;
+    for count in [1, 2, 4, 8, 16, 32, 64, 128, 256] {
+        let sparse = (0..count)
+            .map(|i| (i as u8, full[i].clone()))
+            .collect::<Vec<_>>();
+        let single = common::bench(&format!("sparse/{count}/precomp_wnaf"), || {
+            PrecompWnaf::commit_sparse::<VERKLE_NODE_WIDTH>(&sparse)
+        });
+        common::bench(&format!("sparse/{count}/pippenger"), || {
+            Pippenger::commit_sparse::<VERKLE_NODE_WIDTH>(&sparse)
+        });
+        common::bench(&format!("sparse/{count}/parallel_pippenger"), || {
+            ParallelPippenger::commit_sparse::<VERKLE_NODE_WIDTH>(&sparse)
+        });
+        if sparse_threshold.is_none() && dense_duration < single {
+            sparse_threshold = Some(count);
+        }
+    }
+
+    println!();
+    println!(
+        "measured DENSE_PARALLEL_THRESHOLD: {dense_threshold:?} (current: {})",
+        Auto::DENSE_PARALLEL_THRESHOLD
+    );
+    println!(
+        "measured SPARSE_TO_DENSE_THRESHOLD: {sparse_threshold:?} (current: {})",
+        Auto::SPARSE_TO_DENSE_THRESHOLD
+    );
+}
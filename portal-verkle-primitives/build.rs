@@ -0,0 +1,64 @@
+//! Creates the CRS tables that are embedded with `embedded-crs` feature.
+//!
+//! The tables have the same format as `CRS::tables_to_bytes`, which is checked by the
+//! `embedded_tables_up_to_date` test.
+
+#[cfg(feature = "embedded-crs")]
+#[allow(dead_code)]
+#[path = "src/ec/wnaf.rs"]
+mod wnaf;
+
+fn main() {
+    #[cfg(feature = "embedded-crs")]
+    embedded_crs::write_tables();
+}
+
+#[cfg(feature = "embedded-crs")]
+mod embedded_crs {
+    use std::{env, fs, path::Path};
+
+    use ark_ed_on_bls12_381_bandersnatch::EdwardsAffine;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use sha2::{Digest, Sha256};
+
+    use crate::wnaf::WnafTables;
+
+    const PEDERSEN_SEED: &[u8] = b"eth_verkle_oct_2021";
+    const TABLES_MAGIC: &[u8; 8] = b"VKLCRS02";
+    const VERKLE_NODE_WIDTH: usize = 256;
+
+    pub fn write_tables() {
+        println!("cargo:rerun-if-changed=build.rs");
+        println!("cargo:rerun-if-changed=src/ec/wnaf.rs");
+
+        let bases = (0u64..)
+            .filter_map(|i| {
+                let hash = Sha256::new_with_prefix(PEDERSEN_SEED)
+                    .chain_update(i.to_be_bytes())
+                    .finalize();
+                banderwagon::try_reduce_to_element(&hash)
+            })
+            .take(VERKLE_NODE_WIDTH)
+            .map(|element| {
+                EdwardsAffine::deserialize_uncompressed_unchecked(
+                    &element.to_bytes_uncompressed()[..],
+                )
+                .expect("uncompressed point should deserialize")
+            })
+            .collect::<Vec<_>>();
+        let wnaf_tables = WnafTables::new(&bases);
+
+        let mut bytes = TABLES_MAGIC.to_vec();
+        for point in bases.iter().chain(wnaf_tables.points()) {
+            point
+                .serialize_uncompressed(&mut bytes)
+                .expect("point should serialize into vector");
+        }
+        let checksum = Sha256::digest(&bytes);
+        bytes.extend_from_slice(&checksum);
+
+        let out_dir = env::var("OUT_DIR").expect("OUT_DIR should be set by cargo");
+        fs::write(Path::new(&out_dir).join("crs_tables.bin"), bytes)
+            .expect("CRS tables should be written");
+    }
+}
@@ -1,73 +1,137 @@
-use banderwagon::msm::MSMPrecompWnaf;
-use once_cell::sync::Lazy;
+use std::{any::Any, array, fs, path::Path, sync::OnceLock};
+
+use alloy_primitives::{b256, B256};
+use ark_ed_on_bls12_381_bandersnatch::EdwardsAffine;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use banderwagon::Element;
 use sha2::{Digest, Sha256};
 
-use crate::{constants::VERKLE_NODE_WIDTH, utils::array_long_const, Point, ScalarField};
+use crate::{
+    constants::VERKLE_NODE_WIDTH,
+    utils::{generic_static, DomainSize},
+    CrsTablesError, DefaultMsmBackend, MsmBackend, Point, ScalarField,
+};
+
+use super::wnaf::{WnafTables, TABLE_LEN as WNAF_TABLE_LEN};
 
 const PEDERSEN_SEED: &[u8] = b"eth_verkle_oct_2021";
 
-static PRECOMP_WNAF_WINDOW_SIZE: usize = 12;
+/// The SHA256 hash of all (compressed) bases of the Verkle trie CRS.
+///
+/// Taken from:
+/// https://github.com/crate-crypto/go-ipa/blob/b1e8a79f509c5dd26b44d64c5f4aff67d7e69ed0/ipa/ipa_test.go#L210
+pub const ALL_POINTS_SHA: B256 =
+    b256!("1fcaea10bf24f750200e06fa473c76ff0468007291fa548e2d99f09ba9256fdb");
+
+/// The prefix of the serialized CRS tables, used to identify the format.
+const TABLES_MAGIC: &[u8; 8] = b"VKLCRS02";
+
+/// The length of the serialized bases.
+const BASES_LEN: usize = 64 * VERKLE_NODE_WIDTH;
+
+/// The length of the serialized CRS tables: magic, uncompressed bases, uncompressed points of the
+/// wNAF tables and the checksum.
+const TABLES_LEN: usize =
+    TABLES_MAGIC.len() + BASES_LEN + 64 * WNAF_TABLE_LEN * VERKLE_NODE_WIDTH + 32;
+
+/// The CRS tables, created by the build script.
+#[cfg(feature = "embedded-crs")]
+static EMBEDDED_TABLES: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/crs_tables.bin"));
 
 /// The CRS (Common Reference String) that contains Verkle trie relevant constants
-pub struct CRS {
-    bases: [Point; VERKLE_NODE_WIDTH],
+///
+/// The CRS of smaller domain (`N`) uses the first `N` bases of the Verkle trie CRS.
+pub struct CRS<const N: usize = VERKLE_NODE_WIDTH> {
+    bases: [Point; N],
     generator: Point,
     /// Precomputed wNAF (w-ary non-adjacent form) tables for efficient scalar multiplication.
-    wnaf_precomp: MSMPrecompWnaf,
+    ///
+    /// They are created on first use (unless loaded with the bases), as they are expensive to
+    /// create and not needed by all callers (e.g. ones that only need bases).
+    wnaf_tables: OnceLock<WnafTables>,
+    /// The bases in affine representation, created on first use.
+    affine_bases: OnceLock<[EdwardsAffine; N]>,
 }
 
-static INSTANCE: Lazy<CRS> = Lazy::new(CRS::new);
+static INSTANCE: OnceLock<CRS> = OnceLock::new();
 
-impl CRS {
+impl<const N: usize> CRS<N> {
     fn new() -> Self {
-        let mut generated_elements = 0;
-        let mut elements = array_long_const(Point::zero());
+        let () = DomainSize::<N>::CHECK;
 
-        for i in 0u64.. {
-            if generated_elements == elements.len() {
-                break;
-            }
+        let elements = (0u64..)
+            .filter_map(|i| {
+                let hash = Sha256::new_with_prefix(PEDERSEN_SEED)
+                    .chain_update(i.to_be_bytes())
+                    .finalize();
+                banderwagon::try_reduce_to_element(&hash)
+            })
+            .take(N)
+            .collect::<Vec<_>>();
+
+        Self::from_elements(&elements)
+    }
 
-            let hash = Sha256::new_with_prefix(PEDERSEN_SEED)
-                .chain_update(i.to_be_bytes())
-                .finalize();
+    fn from_elements(elements: &[Element]) -> Self {
+        Self {
+            bases: array::from_fn(|i| Point::new(elements[i])),
+            generator: Point::prime_subgroup_generator(),
+            wnaf_tables: OnceLock::new(),
+            affine_bases: OnceLock::new(),
+        }
+    }
 
-            if let Some(p) = banderwagon::try_reduce_to_element(&hash) {
-                elements[generated_elements] = Point::new(p);
-                generated_elements += 1;
+    fn instance() -> &'static Self {
+        // Avoid locking for the Verkle trie CRS
+        if N == VERKLE_NODE_WIDTH {
+            if let Some(instance) = (CRS::verkle_instance() as &dyn Any).downcast_ref() {
+                return instance;
             }
         }
+        generic_static(Self::new)
+    }
 
-        let wnaf_precomp = MSMPrecompWnaf::new(
-            &elements.each_ref().map(|point| point.inner()),
-            PRECOMP_WNAF_WINDOW_SIZE,
-        );
+    pub(crate) fn wnaf_tables() -> &'static WnafTables {
+        Self::instance()
+            .wnaf_tables
+            .get_or_init(|| WnafTables::new(Self::affine_bases()))
+    }
 
-        Self {
-            bases: elements,
-            generator: Point::prime_subgroup_generator(),
-            wnaf_precomp,
-        }
+    pub fn bases() -> &'static [Point; N] {
+        &Self::instance().bases
     }
 
-    pub fn bases() -> &'static [Point; VERKLE_NODE_WIDTH] {
-        &INSTANCE.bases
+    pub(crate) fn affine_bases() -> &'static [EdwardsAffine; N] {
+        let instance = Self::instance();
+        instance
+            .affine_bases
+            .get_or_init(|| instance.bases.each_ref().map(Point::to_affine))
     }
 
-    pub fn generator() -> &'static Point {
-        &INSTANCE.generator
+    /// Commit to a full vector, using [DefaultMsmBackend].
+    pub fn commit(scalars: &[ScalarField; N]) -> Point {
+        DefaultMsmBackend::commit(scalars)
     }
+}
 
-    /// Commit to a full vector.
-    pub fn commit(scalars: &[ScalarField; VERKLE_NODE_WIDTH]) -> Point {
-        // Preliminary benchmarks indicate that the parallel version is faster
-        // for vectors of length 64 or more
-        let scalars = scalars.each_ref().map(|scalar| scalar.inner());
-        if scalars.len() >= 64 {
-            Point::new(INSTANCE.wnaf_precomp.mul_par(&scalars))
-        } else {
-            Point::new(INSTANCE.wnaf_precomp.mul(&scalars))
-        }
+/// Functionality that is only available for the Verkle trie CRS.
+impl CRS {
+    fn verkle_instance() -> &'static Self {
+        INSTANCE.get_or_init(Self::initialize)
+    }
+
+    #[cfg(feature = "embedded-crs")]
+    fn initialize() -> Self {
+        Self::from_tables(EMBEDDED_TABLES).expect("embedded CRS tables should be valid")
+    }
+
+    #[cfg(not(feature = "embedded-crs"))]
+    fn initialize() -> Self {
+        Self::new()
+    }
+
+    pub fn generator() -> &'static Point {
+        &Self::verkle_instance().generator
     }
 
     /// Single scalar multiplication.
@@ -75,29 +139,117 @@ impl CRS {
         if scalar.is_zero() {
             Point::zero()
         } else {
-            Point::new(
-                INSTANCE
-                    .wnaf_precomp
-                    .mul_index(scalar.inner(), index as usize),
-            )
+            Point::from_projective(Self::wnaf_tables().mul_index(&scalar.inner(), index as usize))
         }
     }
 
-    /// Commit to sparse set of scalars.
+    /// Commit to sparse set of scalars, using [DefaultMsmBackend].
     pub fn commit_sparse(scalars: &[(u8, ScalarField)]) -> Point {
-        // TODO: consider if 64 is good value
-        if scalars.len() >= 64 {
-            let mut dense = array_long_const(ScalarField::zero());
-            for (index, value) in scalars {
-                dense[*index as usize] = value.clone();
-            }
-            Self::commit(&dense)
-        } else {
-            scalars
-                .iter()
-                .map(|(index, value)| Self::commit_single(*index, value))
-                .sum()
+        DefaultMsmBackend::commit_sparse::<VERKLE_NODE_WIDTH>(scalars)
+    }
+
+    /// Serializes the CRS bases and their precomputed wNAF tables, so they can be loaded with
+    /// [load_tables](Self::load_tables) instead of being created again.
+    ///
+    /// All points are stored uncompressed (which avoids the square roots needed to decompress
+    /// them), followed by the SHA256 checksum of everything before it.
+    pub fn tables_to_bytes() -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(TABLES_LEN);
+        bytes.extend_from_slice(TABLES_MAGIC);
+        for base in Self::bases() {
+            bytes.extend_from_slice(&base.inner().to_bytes_uncompressed());
+        }
+        for point in Self::wnaf_tables().points() {
+            point
+                .serialize_uncompressed(&mut bytes)
+                .expect("point should serialize into vector");
+        }
+        let checksum = Sha256::digest(&bytes);
+        bytes.extend_from_slice(&checksum);
+        bytes
+    }
+
+    /// Writes [tables_to_bytes](Self::tables_to_bytes) to the file.
+    pub fn save_tables(path: impl AsRef<Path>) -> Result<(), CrsTablesError> {
+        Ok(fs::write(path, Self::tables_to_bytes())?)
+    }
+
+    /// Initializes the Verkle trie CRS from the serialized tables (see
+    /// [tables_to_bytes](Self::tables_to_bytes)).
+    ///
+    /// Should be called before the CRS is used for the first time, otherwise
+    /// [CrsTablesError::AlreadyInitialized] is returned.
+    ///
+    /// The tables are rejected if their checksum is wrong, if any of their bases is not on the
+    /// curve or if their bases don't match [ALL_POINTS_SHA]. The wNAF tables are only protected by
+    /// the checksum (verifying them would take as long as creating them).
+    pub fn load_tables(bytes: &[u8]) -> Result<(), CrsTablesError> {
+        let crs = Self::from_tables(bytes)?;
+        INSTANCE
+            .set(crs)
+            .map_err(|_| CrsTablesError::AlreadyInitialized)
+    }
+
+    /// Reads the file and loads it using [load_tables](Self::load_tables).
+    pub fn load_tables_from_file(path: impl AsRef<Path>) -> Result<(), CrsTablesError> {
+        Self::load_tables(&fs::read(path)?)
+    }
+
+    fn from_tables(bytes: &[u8]) -> Result<Self, CrsTablesError> {
+        if bytes.len() != TABLES_LEN {
+            return Err(CrsTablesError::InvalidLength(bytes.len()));
+        }
+        let (data, checksum) = bytes.split_at(TABLES_LEN - 32);
+        let Some(points) = data.strip_prefix(TABLES_MAGIC) else {
+            return Err(CrsTablesError::UnknownFormat);
+        };
+        if Sha256::digest(data)[..] != *checksum {
+            return Err(CrsTablesError::ChecksumMismatch);
+        }
+        let (bases, wnaf_points) = points.split_at(BASES_LEN);
+
+        // Every base has to be on the curve. Together with the check against ALL_POINTS_SHA
+        // (compressed form is derived from x and the sign of y), this ensures that bases are
+        // correct.
+        let elements = bases
+            .chunks_exact(64)
+            .enumerate()
+            .map(|(index, chunk)| {
+                let is_on_curve = EdwardsAffine::deserialize_uncompressed_unchecked(chunk)
+                    .is_ok_and(|point| point.is_on_curve());
+                if !is_on_curve {
+                    return Err(CrsTablesError::InvalidBase(index));
+                }
+                let bytes = chunk.try_into().expect("chunk should have 64 bytes");
+                Ok(Element::from_bytes_unchecked_uncompressed(bytes))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut hasher = Sha256::new();
+        for element in &elements {
+            hasher.update(element.to_bytes());
         }
+        let points_sha = B256::from_slice(&hasher.finalize());
+        if points_sha != ALL_POINTS_SHA {
+            return Err(CrsTablesError::IntegrityCheckFailed {
+                expected: ALL_POINTS_SHA,
+                actual: points_sha,
+            });
+        }
+
+        let wnaf_points = wnaf_points
+            .chunks_exact(64)
+            .enumerate()
+            .map(|(index, chunk)| {
+                EdwardsAffine::deserialize_uncompressed_unchecked(chunk)
+                    .map_err(|_| CrsTablesError::InvalidWnafTablePoint(index))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            wnaf_tables: OnceLock::from(WnafTables::from_points(wnaf_points)),
+            ..Self::from_elements(&elements)
+        })
     }
 }
 
@@ -105,9 +257,10 @@ impl CRS {
 mod tests {
     use std::str::FromStr;
 
-    use alloy_primitives::B256;
     use ark_serialize::Valid;
 
+    use crate::utils::array_long_const;
+
     use super::*;
 
     // Taken from:
@@ -115,42 +268,147 @@ mod tests {
 
     const FIRST_POINT: &str = "0x01587ad1336675eb912550ec2a28eb8923b824b490dd2ba82e48f14590a298a0";
     const LAST_POINT: &str = "0x3de2be346b539395b0c0de56a5ccca54a317f1b5c80107b0802af9a62276a4d8";
-    const ALL_POINTS_SHA: &str =
-        "0x1fcaea10bf24f750200e06fa473c76ff0468007291fa548e2d99f09ba9256fdb";
 
     #[test]
     fn first_point() -> anyhow::Result<()> {
-        assert_eq!(B256::from(&CRS::bases()[0]), B256::from_str(FIRST_POINT)?);
+        assert_eq!(
+            B256::from(&CRS::<VERKLE_NODE_WIDTH>::bases()[0]),
+            B256::from_str(FIRST_POINT)?
+        );
         Ok(())
     }
 
     #[test]
     fn last_point() -> anyhow::Result<()> {
-        assert_eq!(B256::from(&CRS::bases()[255]), B256::from_str(LAST_POINT)?);
+        assert_eq!(
+            B256::from(&CRS::<VERKLE_NODE_WIDTH>::bases()[255]),
+            B256::from_str(LAST_POINT)?
+        );
         Ok(())
     }
 
     #[test]
     fn all_points_hash() -> anyhow::Result<()> {
         let mut hasher = Sha256::new();
-        for p in CRS::bases() {
+        for p in CRS::<VERKLE_NODE_WIDTH>::bases() {
             hasher.update(B256::from(p));
         }
 
-        assert_eq!(
-            B256::from_slice(&hasher.finalize()),
-            B256::from_str(ALL_POINTS_SHA)?
-        );
+        assert_eq!(B256::from_slice(&hasher.finalize()), ALL_POINTS_SHA);
         Ok(())
     }
 
     #[test]
     fn valid() {
-        for (i, p) in CRS::bases().iter().enumerate() {
+        for (i, p) in CRS::<VERKLE_NODE_WIDTH>::bases().iter().enumerate() {
             assert!(
                 p.inner().check().is_ok(),
                 "point {p:?} at index {i} should be valid"
             );
         }
     }
+
+    #[test]
+    fn smaller_domain() {
+        assert_eq!(CRS::<16>::bases(), &CRS::<VERKLE_NODE_WIDTH>::bases()[..16]);
+
+        let scalars: [ScalarField; 16] = array::from_fn(ScalarField::from);
+        let mut scalars_long = array_long_const(ScalarField::zero());
+        scalars_long[..16].clone_from_slice(&scalars);
+        assert_eq!(CRS::commit(&scalars), CRS::commit(&scalars_long));
+    }
+
+    #[test]
+    fn tables_round_trip() -> anyhow::Result<()> {
+        let crs = CRS::from_tables(&CRS::tables_to_bytes())?;
+        assert_eq!(&crs.bases, CRS::<VERKLE_NODE_WIDTH>::bases());
+        assert_eq!(&crs.generator, CRS::generator());
+        assert_eq!(
+            crs.wnaf_tables.get().map(WnafTables::points),
+            Some(CRS::<VERKLE_NODE_WIDTH>::wnaf_tables().points())
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-crs")]
+    fn embedded_tables_up_to_date() {
+        assert_eq!(EMBEDDED_TABLES, CRS::tables_to_bytes());
+        assert_eq!(
+            WnafTables::new(CRS::<VERKLE_NODE_WIDTH>::affine_bases()).points(),
+            CRS::<VERKLE_NODE_WIDTH>::wnaf_tables().points(),
+            "embedded wNAF tables should be the same as created ones"
+        );
+    }
+
+    #[test]
+    fn load_tables_already_initialized() {
+        // Make sure that CRS is initialized
+        CRS::generator();
+        assert!(matches!(
+            CRS::load_tables(&CRS::tables_to_bytes()),
+            Err(CrsTablesError::AlreadyInitialized)
+        ));
+    }
+
+    #[test]
+    fn tables_invalid_length() {
+        let mut bytes = CRS::tables_to_bytes();
+        bytes.pop();
+        assert!(matches!(
+            CRS::from_tables(&bytes),
+            Err(CrsTablesError::InvalidLength(len)) if len == TABLES_LEN - 1
+        ));
+    }
+
+    #[test]
+    fn tables_unknown_format() {
+        let mut bytes = CRS::tables_to_bytes();
+        bytes[0] ^= 1;
+        assert!(matches!(
+            CRS::from_tables(&bytes),
+            Err(CrsTablesError::UnknownFormat)
+        ));
+    }
+
+    #[test]
+    fn tables_checksum_mismatch() {
+        let mut bytes = CRS::tables_to_bytes();
+        bytes[TABLES_MAGIC.len()] ^= 1;
+        assert!(matches!(
+            CRS::from_tables(&bytes),
+            Err(CrsTablesError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn tables_base_not_on_curve() {
+        // Change y of the second base and fix the checksum
+        let mut bytes = CRS::tables_to_bytes();
+        bytes[TABLES_MAGIC.len() + 64 + 32] ^= 1;
+        let data_len = TABLES_LEN - 32;
+        let checksum = Sha256::digest(&bytes[..data_len]);
+        bytes[data_len..].copy_from_slice(&checksum);
+
+        assert!(matches!(
+            CRS::from_tables(&bytes),
+            Err(CrsTablesError::InvalidBase(1))
+        ));
+    }
+
+    #[test]
+    fn tables_wrong_bases() {
+        // Swap first two bases and fix the checksum
+        let mut bytes = CRS::tables_to_bytes();
+        let (magic_and_first, rest) = bytes.split_at_mut(TABLES_MAGIC.len() + 64);
+        magic_and_first[TABLES_MAGIC.len()..].swap_with_slice(&mut rest[..64]);
+        let data_len = TABLES_LEN - 32;
+        let checksum = Sha256::digest(&bytes[..data_len]);
+        bytes[data_len..].copy_from_slice(&checksum);
+
+        assert!(matches!(
+            CRS::from_tables(&bytes),
+            Err(CrsTablesError::IntegrityCheckFailed { expected, .. }) if expected == ALL_POINTS_SHA
+        ));
+    }
 }
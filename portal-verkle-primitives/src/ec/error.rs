@@ -0,0 +1,40 @@
+use std::io;
+
+use alloy_primitives::B256;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EcDecodeError {
+    #[error("Bytes are not a valid encoding of a point in the Banderwagon subgroup")]
+    InvalidPoint,
+
+    #[error("Scalar is not below the field modulus")]
+    NonCanonicalScalar,
+}
+
+#[derive(Debug, Error)]
+pub enum CrsTablesError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    #[error("Invalid length of CRS tables: {0}")]
+    InvalidLength(usize),
+
+    #[error("Unknown format of CRS tables")]
+    UnknownFormat,
+
+    #[error("Checksum of CRS tables doesn't match")]
+    ChecksumMismatch,
+
+    #[error("CRS base at index {0} is not on the curve")]
+    InvalidBase(usize),
+
+    #[error("Point at index {0} of the CRS wNAF tables is not valid")]
+    InvalidWnafTablePoint(usize),
+
+    #[error("Hash of CRS bases doesn't match. expected: {expected} actual: {actual}")]
+    IntegrityCheckFailed { expected: B256, actual: B256 },
+
+    #[error("CRS is already initialized")]
+    AlreadyInitialized,
+}
@@ -1,7 +1,15 @@
-pub use crs::CRS;
+pub use crs::{ALL_POINTS_SHA, CRS};
+pub use error::{CrsTablesError, EcDecodeError};
+pub use msm::{
+    Auto, DefaultMsmBackend, MsmBackend, ParallelPippenger, ParallelPrecompWnaf, Pippenger,
+    PrecompWnaf,
+};
 pub use point::Point;
 pub use scalar_field::{BatchInversion, DotProduct, ScalarField};
 
 mod crs;
+mod error;
+mod msm;
 mod point;
 mod scalar_field;
+mod wnaf;
@@ -0,0 +1,309 @@
+use std::array;
+
+#[cfg(feature = "parallel")]
+use itertools::Itertools;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{Point, ScalarField, CRS};
+
+/// The algorithm used for the multi-scalar multiplication (MSM) with the [CRS] bases.
+///
+/// The [DefaultMsmBackend] is used by [CRS::commit] and [CRS::commit_sparse]. Other backends can
+/// be used directly at the call site (e.g. `Pippenger::commit(&scalars)`).
+pub trait MsmBackend {
+    /// Commit to a full vector.
+    fn commit<const N: usize>(scalars: &[ScalarField; N]) -> Point;
+
+    /// Commit to sparse set of scalars.
+    ///
+    /// All indices should be smaller than `N`. This is checked only in debug builds, otherwise the
+    /// backend can panic or return the wrong commitment.
+    fn commit_sparse<const N: usize>(scalars: &[(u8, ScalarField)]) -> Point {
+        Self::commit(&to_dense::<N>(scalars))
+    }
+}
+
+fn debug_assert_in_domain<const N: usize>(scalars: &[(u8, ScalarField)]) {
+    debug_assert!(
+        scalars.iter().all(|(index, _)| (*index as usize) < N),
+        "Index is outside of the domain of size {N}"
+    );
+}
+
+fn to_dense<const N: usize>(scalars: &[(u8, ScalarField)]) -> [ScalarField; N] {
+    debug_assert_in_domain::<N>(scalars);
+    let mut dense = array::from_fn(|_| ScalarField::zero());
+    for (index, value) in scalars {
+        dense[*index as usize] = value.clone();
+    }
+    dense
+}
+
+/// The backend that is used when no other backend is specified.
+///
+/// It's [Auto], unless `msm-pippenger` feature is enabled (in which case it's [Pippenger]).
+#[cfg(not(feature = "msm-pippenger"))]
+pub type DefaultMsmBackend = Auto;
+
+/// The backend that is used when no other backend is specified.
+///
+/// It's [Auto], unless `msm-pippenger` feature is enabled (in which case it's [Pippenger]).
+#[cfg(feature = "msm-pippenger")]
+pub type DefaultMsmBackend = Pippenger;
+
+/// Uses precomputed wNAF (w-ary non-adjacent form) tables on a single thread.
+#[derive(Debug, Clone, Copy)]
+pub struct PrecompWnaf;
+
+impl MsmBackend for PrecompWnaf {
+    fn commit<const N: usize>(scalars: &[ScalarField; N]) -> Point {
+        let scalars = scalars.each_ref().map(ScalarField::inner);
+        Point::from_projective(CRS::<N>::wnaf_tables().mul(&scalars))
+    }
+
+    /// Single scalar multiplication for each scalar, using precomputed tables.
+    fn commit_sparse<const N: usize>(scalars: &[(u8, ScalarField)]) -> Point {
+        debug_assert_in_domain::<N>(scalars);
+        let wnaf_tables = CRS::<N>::wnaf_tables();
+        let result = scalars
+            .iter()
+            .filter(|(_, scalar)| !scalar.is_zero())
+            .map(|(index, scalar)| wnaf_tables.mul_index(&scalar.inner(), *index as usize))
+            .sum();
+        Point::from_projective(result)
+    }
+}
+
+/// Uses precomputed wNAF (w-ary non-adjacent form) tables, in parallel.
+///
+/// Scalars are processed in parallel only if `parallel` feature is enabled. Otherwise, this is the
+/// same as [PrecompWnaf].
+#[derive(Debug, Clone, Copy)]
+pub struct ParallelPrecompWnaf;
+
+impl MsmBackend for ParallelPrecompWnaf {
+    fn commit<const N: usize>(scalars: &[ScalarField; N]) -> Point {
+        #[cfg(feature = "parallel")]
+        {
+            let wnaf_tables = CRS::<N>::wnaf_tables();
+            let result = scalars
+                .par_iter()
+                .enumerate()
+                .filter(|(_, scalar)| !scalar.is_zero())
+                .map(|(index, scalar)| wnaf_tables.mul_index(&scalar.inner(), index))
+                .sum();
+            Point::from_projective(result)
+        }
+        #[cfg(not(feature = "parallel"))]
+        PrecompWnaf::commit(scalars)
+    }
+}
+
+/// Uses Pippenger's algorithm (as implemented by ark-ec), without any precomputation.
+#[derive(Debug, Clone, Copy)]
+pub struct Pippenger;
+
+impl MsmBackend for Pippenger {
+    fn commit<const N: usize>(scalars: &[ScalarField; N]) -> Point {
+        Point::multi_scalar_mul(CRS::<N>::bases(), scalars)
+    }
+
+    /// Only bases of non-zero scalars are used.
+    fn commit_sparse<const N: usize>(scalars: &[(u8, ScalarField)]) -> Point {
+        debug_assert_in_domain::<N>(scalars);
+        let bases = CRS::<N>::bases();
+        let (points, scalars): (Vec<_>, Vec<_>) = scalars
+            .iter()
+            .filter(|(_, scalar)| !scalar.is_zero())
+            .map(|(index, scalar)| (&bases[*index as usize], scalar))
+            .unzip();
+        Point::multi_scalar_mul(points, scalars)
+    }
+}
+
+/// Same as [Pippenger], except that scalars are split into chunks that are processed in parallel
+/// (using rayon).
+///
+/// Chunks are processed in parallel only if `parallel` feature is enabled. Otherwise, this is the
+/// same as [Pippenger].
+#[derive(Debug, Clone, Copy)]
+pub struct ParallelPippenger;
+
+#[cfg(feature = "parallel")]
+impl ParallelPippenger {
+    const CHUNK_SIZE: usize = 32;
+}
+
+impl MsmBackend for ParallelPippenger {
+    fn commit<const N: usize>(scalars: &[ScalarField; N]) -> Point {
+        #[cfg(feature = "parallel")]
+        return CRS::<N>::bases()
+            .par_chunks(Self::CHUNK_SIZE)
+            .zip(scalars.par_chunks(Self::CHUNK_SIZE))
+            .map(|(points, scalars)| Point::multi_scalar_mul(points, scalars))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .sum();
+        #[cfg(not(feature = "parallel"))]
+        return Pippenger::commit(scalars);
+    }
+
+    fn commit_sparse<const N: usize>(scalars: &[(u8, ScalarField)]) -> Point {
+        #[cfg(feature = "parallel")]
+        {
+            debug_assert_in_domain::<N>(scalars);
+            let bases = CRS::<N>::bases();
+            let scalars = scalars
+                .iter()
+                .filter(|(_, scalar)| !scalar.is_zero())
+                .collect_vec();
+            scalars
+                .par_chunks(Self::CHUNK_SIZE)
+                .map(|chunk| {
+                    Point::multi_scalar_mul(
+                        chunk.iter().map(|(index, _)| &bases[*index as usize]),
+                        chunk.iter().map(|(_, scalar)| scalar),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .sum()
+        }
+        #[cfg(not(feature = "parallel"))]
+        Pippenger::commit_sparse::<N>(scalars)
+    }
+}
+
+/// Picks between [PrecompWnaf] and [ParallelPrecompWnaf], based on the number of scalars.
+///
+/// The thresholds can be re-evaluated with `cargo bench --bench msm`.
+#[derive(Debug, Clone, Copy)]
+pub struct Auto;
+
+impl Auto {
+    /// The minimal domain size for which [ParallelPrecompWnaf] is used for full vectors.
+    ///
+    /// Measured with `cargo bench --bench msm --features parallel` (on a single core), serial vs
+    /// parallel wNAF: 13.93ms vs 14.35ms for 128 scalars and 18.54ms vs 18.29ms for 256 scalars.
+    pub const DENSE_PARALLEL_THRESHOLD: usize = 256;
+
+    /// The minimal number of scalars for which the sparse set is committed to as full vector.
+    ///
+    /// Measured with `cargo bench --bench msm --features parallel` (on a single core), the full
+    /// vector takes 18.80ms, while single scalar multiplications take 12.76ms for 128 scalars and
+    /// 24.26ms for 256 scalars.
+    pub const SPARSE_TO_DENSE_THRESHOLD: usize = 256;
+}
+
+impl MsmBackend for Auto {
+    fn commit<const N: usize>(scalars: &[ScalarField; N]) -> Point {
+        if N >= Self::DENSE_PARALLEL_THRESHOLD {
+            ParallelPrecompWnaf::commit(scalars)
+        } else {
+            PrecompWnaf::commit(scalars)
+        }
+    }
+
+    fn commit_sparse<const N: usize>(scalars: &[(u8, ScalarField)]) -> Point {
+        if scalars.len() >= Self::SPARSE_TO_DENSE_THRESHOLD {
+            Self::commit(&to_dense::<N>(scalars))
+        } else {
+            PrecompWnaf::commit_sparse::<N>(scalars)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::{constants::VERKLE_NODE_WIDTH, utils::array_long};
+
+    use super::*;
+
+    fn scalars() -> [ScalarField; VERKLE_NODE_WIDTH] {
+        array_long(|i| ScalarField::from(i as u64 * 1_000_003 + 7))
+    }
+
+    fn sparse_scalars() -> Vec<(u8, ScalarField)> {
+        vec![
+            (0, ScalarField::from(1u64)),
+            (5, ScalarField::zero()),
+            (17, ScalarField::from(12345u64)),
+            (255, -ScalarField::one()),
+        ]
+    }
+
+    #[test]
+    fn commit_same_for_all_backends() {
+        let scalars = scalars();
+        let expected = Point::multi_scalar_mul(CRS::<VERKLE_NODE_WIDTH>::bases(), &scalars);
+        assert_eq!(PrecompWnaf::commit(&scalars), expected);
+        assert_eq!(ParallelPrecompWnaf::commit(&scalars), expected);
+        assert_eq!(Pippenger::commit(&scalars), expected);
+        assert_eq!(ParallelPippenger::commit(&scalars), expected);
+        assert_eq!(Auto::commit(&scalars), expected);
+    }
+
+    #[test]
+    fn commit_smaller_domain() {
+        let scalars: [ScalarField; 16] = array::from_fn(ScalarField::from);
+        let expected = Point::multi_scalar_mul(CRS::<16>::bases(), &scalars);
+        assert_eq!(PrecompWnaf::commit(&scalars), expected);
+        assert_eq!(ParallelPrecompWnaf::commit(&scalars), expected);
+        assert_eq!(Pippenger::commit(&scalars), expected);
+        assert_eq!(ParallelPippenger::commit(&scalars), expected);
+        assert_eq!(Auto::commit(&scalars), expected);
+    }
+
+    #[test]
+    fn commit_sparse_same_for_all_backends() {
+        let scalars = sparse_scalars();
+        let expected = Point::multi_scalar_mul(
+            CRS::<VERKLE_NODE_WIDTH>::bases(),
+            &to_dense::<VERKLE_NODE_WIDTH>(&scalars),
+        );
+
+        assert_eq!(
+            PrecompWnaf::commit_sparse::<VERKLE_NODE_WIDTH>(&scalars),
+            expected
+        );
+        assert_eq!(
+            ParallelPrecompWnaf::commit_sparse::<VERKLE_NODE_WIDTH>(&scalars),
+            expected
+        );
+        assert_eq!(
+            Pippenger::commit_sparse::<VERKLE_NODE_WIDTH>(&scalars),
+            expected
+        );
+        assert_eq!(
+            ParallelPippenger::commit_sparse::<VERKLE_NODE_WIDTH>(&scalars),
+            expected
+        );
+        assert_eq!(Auto::commit_sparse::<VERKLE_NODE_WIDTH>(&scalars), expected);
+    }
+
+    #[rstest]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Index is outside of the domain")]
+    fn commit_sparse_outside_of_domain(
+        #[values(
+            PrecompWnaf::commit_sparse::<16>,
+            ParallelPrecompWnaf::commit_sparse::<16>,
+            Pippenger::commit_sparse::<16>,
+            ParallelPippenger::commit_sparse::<16>,
+            Auto::commit_sparse::<16>
+        )]
+        commit_sparse: fn(&[(u8, ScalarField)]) -> Point,
+    ) {
+        commit_sparse(&[(16, ScalarField::one())]);
+    }
+
+    #[test]
+    fn commit_sparse_empty() {
+        assert!(PrecompWnaf::commit_sparse::<VERKLE_NODE_WIDTH>(&[]).is_zero());
+        assert!(Pippenger::commit_sparse::<VERKLE_NODE_WIDTH>(&[]).is_zero());
+        assert!(ParallelPippenger::commit_sparse::<VERKLE_NODE_WIDTH>(&[]).is_zero());
+    }
+}
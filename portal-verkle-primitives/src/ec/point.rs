@@ -1,6 +1,7 @@
 use std::{fmt::Debug, iter::Sum, ops};
 
 use alloy_primitives::B256;
+use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, EdwardsProjective};
 use banderwagon::{CanonicalDeserialize, CanonicalSerialize, Element};
 use derive_more::Constructor;
 use itertools::Itertools;
@@ -8,7 +9,7 @@ use overload::overload;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use ssz::{Decode, Encode};
 
-use crate::ScalarField;
+use crate::{EcDecodeError, ScalarField};
 
 #[derive(Clone, PartialEq, Eq, Constructor)]
 pub struct Point(Element);
@@ -18,6 +19,21 @@ impl Point {
         self.0
     }
 
+    /// Converts the point to its affine representation (requires a field inversion).
+    pub(crate) fn to_affine(&self) -> EdwardsAffine {
+        EdwardsAffine::deserialize_uncompressed_unchecked(&self.0.to_bytes_uncompressed()[..])
+            .expect("uncompressed point should deserialize")
+    }
+
+    /// Creates the point from the arkworks representation (requires a field inversion).
+    pub(crate) fn from_projective(point: EdwardsProjective) -> Self {
+        let mut bytes = [0; 64];
+        point
+            .serialize_uncompressed(&mut bytes[..])
+            .expect("point should serialize into 64 bytes");
+        Self(Element::from_bytes_unchecked_uncompressed(bytes))
+    }
+
     pub fn prime_subgroup_generator() -> Self {
         Self(Element::prime_subgroup_generator())
     }
@@ -26,6 +42,18 @@ impl Point {
         ScalarField::new(self.0.map_to_scalar_field())
     }
 
+    /// Same as [map_to_scalar_field](Self::map_to_scalar_field), but for many points at once.
+    ///
+    /// This is significantly faster than mapping points one by one, as it requires a single field
+    /// inversion (using Montgomery's batch inversion trick).
+    pub fn batch_map_to_scalar_field(points: &[Self]) -> Vec<ScalarField> {
+        let elements = points.iter().map(Self::inner).collect_vec();
+        Element::batch_map_to_scalar_field(&elements)
+            .into_iter()
+            .map(ScalarField::new)
+            .collect()
+    }
+
     pub fn zero() -> Self {
         Self(Element::zero())
     }
@@ -48,12 +76,22 @@ impl Point {
     }
 }
 
-impl From<&B256> for Point {
-    fn from(value: &B256) -> Self {
-        Self(
-            Element::deserialize_compressed(value.as_slice())
-                .expect("ScalarFieldValue should deserialize from B256"),
-        )
+impl TryFrom<&B256> for Point {
+    type Error = EcDecodeError;
+
+    /// Decodes the point, rejecting points that are not in the Banderwagon subgroup.
+    fn try_from(value: &B256) -> Result<Self, Self::Error> {
+        Element::deserialize_compressed(value.as_slice())
+            .map(Self)
+            .map_err(|_| EcDecodeError::InvalidPoint)
+    }
+}
+
+impl TryFrom<B256> for Point {
+    type Error = EcDecodeError;
+
+    fn try_from(value: B256) -> Result<Self, Self::Error> {
+        Self::try_from(&value)
     }
 }
 
@@ -76,7 +114,7 @@ impl Serialize for Point {
 
 impl<'de> Deserialize<'de> for Point {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        Ok(Self::from(&B256::deserialize(deserializer)?))
+        Self::try_from(&B256::deserialize(deserializer)?).map_err(serde::de::Error::custom)
     }
 }
 
@@ -109,11 +147,10 @@ impl Decode for Point {
     }
 
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
-        Element::deserialize_compressed(bytes)
-            .map(Self::new)
-            .map_err(|err| {
-                ssz::DecodeError::BytesInvalid(format!("Error decoding EllipticCurvePoint: {err}"))
-            })
+        let bytes = B256::from_ssz_bytes(bytes)?;
+        Self::try_from(&bytes).map_err(|err| {
+            ssz::DecodeError::BytesInvalid(format!("Error decoding EllipticCurvePoint: {err}"))
+        })
     }
 }
 
@@ -146,3 +183,48 @@ impl<'a> Sum<&'a Self> for Point {
         iter.fold(Self::zero(), |sum, item| sum + item)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::b256;
+
+    use super::*;
+
+    /// The `x` coordinate of the point on the curve that is not in the Banderwagon subgroup.
+    const NOT_IN_SUBGROUP: B256 =
+        b256!("0000000000000000000000000000000000000000000000000000000000000007");
+    /// The `x` coordinate of the point that is not on the curve.
+    const NOT_ON_CURVE: B256 =
+        b256!("0000000000000000000000000000000000000000000000000000000000000002");
+
+    #[test]
+    fn batch_map_to_scalar_field() {
+        let points = [
+            Point::prime_subgroup_generator(),
+            Point::zero(),
+            Point::prime_subgroup_generator().mul(&ScalarField::from(1234u64)),
+        ];
+        assert_eq!(
+            Point::batch_map_to_scalar_field(&points),
+            points.iter().map(Point::map_to_scalar_field).collect_vec()
+        );
+        assert!(Point::batch_map_to_scalar_field(&[]).is_empty());
+    }
+
+    #[test]
+    fn try_from_b256() {
+        let generator = Point::prime_subgroup_generator();
+        assert_eq!(Point::try_from(B256::from(&generator)), Ok(generator));
+
+        for invalid in [NOT_IN_SUBGROUP, NOT_ON_CURVE, B256::repeat_byte(0xff)] {
+            assert_eq!(Point::try_from(invalid), Err(EcDecodeError::InvalidPoint));
+        }
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert!(serde_json::from_str::<Point>(&format!("\"{NOT_IN_SUBGROUP}\"")).is_err());
+        assert!(Point::from_ssz_bytes(NOT_IN_SUBGROUP.as_slice()).is_err());
+        assert!(Point::from_ssz_bytes(&[1; 31]).is_err());
+    }
+}
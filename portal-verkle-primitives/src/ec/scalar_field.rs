@@ -13,7 +13,7 @@ use overload::overload;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use ssz::{Decode, Encode};
 
-use crate::Stem;
+use crate::{EcDecodeError, Stem};
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Constructor)]
 pub struct ScalarField(Fr);
@@ -27,11 +27,11 @@ impl ScalarField {
         Self(Fr::from_le_bytes_mod_order(bytes))
     }
 
-    pub(crate) fn from_be_bytes(bytes: B256) -> Self {
-        Self(
-            Fr::deserialize_compressed(bytes.as_slice())
-                .expect("ScalarFieldValue should deserialize from B256"),
-        )
+    /// Decodes the scalar, rejecting values that are not below the field modulus.
+    pub(crate) fn try_from_be_bytes(bytes: B256) -> Result<Self, EcDecodeError> {
+        Fr::deserialize_compressed(bytes.as_slice())
+            .map(Self)
+            .map_err(|_| EcDecodeError::NonCanonicalScalar)
     }
 
     pub(crate) fn to_be_bytes(&self) -> B256 {
@@ -66,11 +66,13 @@ impl ScalarField {
     }
 }
 
-impl From<B256> for ScalarField {
-    fn from(mut value: B256) -> Self {
+impl TryFrom<B256> for ScalarField {
+    type Error = EcDecodeError;
+
+    fn try_from(mut value: B256) -> Result<Self, Self::Error> {
         // Reverse from little-ending ordering
         value.reverse();
-        Self::from_be_bytes(value)
+        Self::try_from_be_bytes(value)
     }
 }
 
@@ -115,7 +117,7 @@ impl Serialize for ScalarField {
 
 impl<'de> Deserialize<'de> for ScalarField {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        B256::deserialize(deserializer).map(Self::from)
+        Self::try_from(B256::deserialize(deserializer)?).map_err(serde::de::Error::custom)
     }
 }
 
@@ -147,7 +149,9 @@ impl Decode for ScalarField {
     }
 
     fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
-        B256::from_ssz_bytes(bytes).map(Self::from)
+        Self::try_from(B256::from_ssz_bytes(bytes)?).map_err(|err| {
+            ssz::DecodeError::BytesInvalid(format!("Error decoding ScalarField: {err}"))
+        })
     }
 }
 
@@ -250,8 +254,31 @@ impl<'a, 'b> DotProduct<&'a ScalarField, &'b ScalarField> for ScalarField {}
 
 #[cfg(test)]
 mod tests {
+    use ark_ff::BigInteger;
+
     use super::*;
 
+    #[test]
+    fn try_from_b256() {
+        let value = ScalarField::from(1234u64);
+        assert_eq!(ScalarField::try_from(B256::from(&value)), Ok(value));
+
+        let mut modulus = B256::from_slice(&Fr::MODULUS.to_bytes_be());
+        assert_eq!(
+            ScalarField::try_from(modulus),
+            Err(EcDecodeError::NonCanonicalScalar)
+        );
+        modulus[31] -= 1;
+        assert_eq!(ScalarField::try_from(modulus), Ok(-ScalarField::one()));
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let invalid = B256::repeat_byte(0xff);
+        assert!(serde_json::from_str::<ScalarField>(&format!("\"{invalid}\"")).is_err());
+        assert!(ScalarField::from_ssz_bytes(invalid.as_slice()).is_err());
+    }
+
     #[test]
     fn batch_inversion_and_multiplication() {
         let values = vec![
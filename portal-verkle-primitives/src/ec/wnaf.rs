@@ -0,0 +1,84 @@
+use std::{cmp::Ordering, iter};
+
+use ark_ec::{AffineRepr, CurveGroup, Group};
+use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, EdwardsProjective, Fr};
+use ark_ff::{BigInteger, PrimeField, Zero};
+
+/// The window size of the wNAF (w-ary non-adjacent form) representation of scalars.
+pub(crate) const WINDOW_SIZE: usize = 12;
+
+/// The number of precomputed points per base.
+///
+/// Digits of the wNAF representation are odd and smaller than `2^(w-1)` in absolute value, so
+/// only `[1, 3, 5, ..., 2^(w-1) - 1] * base` are needed.
+pub(crate) const TABLE_LEN: usize = 1 << (WINDOW_SIZE - 2);
+
+/// Precomputed wNAF tables of fixed bases.
+#[derive(Debug, Clone)]
+pub(crate) struct WnafTables {
+    /// The tables of all bases, one after another.
+    points: Vec<EdwardsAffine>,
+}
+
+impl WnafTables {
+    pub(crate) fn new(bases: &[EdwardsAffine]) -> Self {
+        let points = bases
+            .iter()
+            .flat_map(|base| {
+                let double = base.into_group().double();
+                iter::successors(Some(base.into_group()), move |point| Some(*point + double))
+                    .take(TABLE_LEN)
+            })
+            .collect::<Vec<_>>();
+        Self {
+            points: EdwardsProjective::normalize_batch(&points),
+        }
+    }
+
+    /// Creates the tables from [points](Self::points).
+    ///
+    /// The points are not checked, so they should come from a trusted source.
+    pub(crate) fn from_points(points: Vec<EdwardsAffine>) -> Self {
+        assert_eq!(
+            points.len() % TABLE_LEN,
+            0,
+            "wNAF tables should have {TABLE_LEN} points per base"
+        );
+        Self { points }
+    }
+
+    /// The points of all tables, one table after another.
+    pub(crate) fn points(&self) -> &[EdwardsAffine] {
+        &self.points
+    }
+
+    /// Multiplies the base at given index with the scalar.
+    pub(crate) fn mul_index(&self, scalar: &Fr, index: usize) -> EdwardsProjective {
+        let table = &self.points[index * TABLE_LEN..][..TABLE_LEN];
+        let wnaf = scalar
+            .into_bigint()
+            .find_wnaf(WINDOW_SIZE)
+            .expect("window size should be valid");
+
+        let mut result = EdwardsProjective::zero();
+        for digit in wnaf.into_iter().rev() {
+            result.double_in_place();
+            match digit.cmp(&0) {
+                Ordering::Greater => result += table[digit as usize / 2],
+                Ordering::Less => result -= table[digit.unsigned_abs() as usize / 2],
+                Ordering::Equal => {}
+            }
+        }
+        result
+    }
+
+    /// Multiplies every base with the scalar at the same index and returns the sum.
+    pub(crate) fn mul(&self, scalars: &[Fr]) -> EdwardsProjective {
+        scalars
+            .iter()
+            .enumerate()
+            .filter(|(_, scalar)| !scalar.is_zero())
+            .map(|(index, scalar)| self.mul_index(scalar, index))
+            .sum()
+    }
+}
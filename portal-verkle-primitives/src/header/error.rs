@@ -0,0 +1,14 @@
+use alloy_primitives::B256;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum HeaderError {
+    #[error("Failed to decode header: {0}")]
+    Decode(#[from] alloy_rlp::Error),
+
+    #[error("Header is followed by {0} unexpected bytes")]
+    TrailingBytes(usize),
+
+    #[error("Block hash doesn't match. expected: {expected} actual: {actual}")]
+    WrongBlockHash { expected: B256, actual: B256 },
+}
@@ -0,0 +1,248 @@
+use alloy_primitives::{keccak256, Address, Bloom, Bytes, B256, B64, U256};
+use alloy_rlp::{BufMut, Decodable, Encodable};
+
+use super::HeaderError;
+
+/// The header of the execution block.
+///
+/// Fields that were added by later forks are optional. They are present only if all fields
+/// before them are present as well.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionHeader {
+    pub parent_hash: B256,
+    pub ommers_hash: B256,
+    pub beneficiary: Address,
+    pub state_root: B256,
+    pub transactions_root: B256,
+    pub receipts_root: B256,
+    pub logs_bloom: Bloom,
+    pub difficulty: U256,
+    pub number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: Bytes,
+    pub mix_hash: B256,
+    pub nonce: B64,
+    /// Added by London fork ([EIP-1559](https://eips.ethereum.org/EIPS/eip-1559)).
+    pub base_fee_per_gas: Option<u64>,
+    /// Added by Shanghai fork ([EIP-4895](https://eips.ethereum.org/EIPS/eip-4895)).
+    pub withdrawals_root: Option<B256>,
+    /// Added by Cancun fork ([EIP-4844](https://eips.ethereum.org/EIPS/eip-4844)).
+    pub blob_gas_used: Option<u64>,
+    /// Added by Cancun fork ([EIP-4844](https://eips.ethereum.org/EIPS/eip-4844)).
+    pub excess_blob_gas: Option<u64>,
+    /// Added by Cancun fork ([EIP-4788](https://eips.ethereum.org/EIPS/eip-4788)).
+    pub parent_beacon_block_root: Option<B256>,
+    /// Added by Prague fork ([EIP-7685](https://eips.ethereum.org/EIPS/eip-7685)).
+    pub requests_root: Option<B256>,
+}
+
+impl ExecutionHeader {
+    /// Decodes the RLP encoded header.
+    pub fn decode(rlp: &[u8]) -> Result<Self, HeaderError> {
+        let mut buf = rlp;
+        let header = <Self as Decodable>::decode(&mut buf)?;
+        if !buf.is_empty() {
+            return Err(HeaderError::TrailingBytes(buf.len()));
+        }
+        Ok(header)
+    }
+
+    /// Decodes the RLP encoded header and checks that its hash matches the expected block hash.
+    pub fn decode_and_verify(rlp: &[u8], block_hash: &B256) -> Result<Self, HeaderError> {
+        let header = Self::decode(rlp)?;
+        let actual = keccak256(rlp);
+        if &actual != block_hash {
+            return Err(HeaderError::WrongBlockHash {
+                expected: *block_hash,
+                actual,
+            });
+        }
+        Ok(header)
+    }
+
+    /// Returns the block hash, which is keccak hash of the RLP encoded header.
+    pub fn hash(&self) -> B256 {
+        keccak256(alloy_rlp::encode(self))
+    }
+
+    fn payload_length(&self) -> usize {
+        let mut length = self.parent_hash.length()
+            + self.ommers_hash.length()
+            + self.beneficiary.length()
+            + self.state_root.length()
+            + self.transactions_root.length()
+            + self.receipts_root.length()
+            + self.logs_bloom.length()
+            + self.difficulty.length()
+            + self.number.length()
+            + self.gas_limit.length()
+            + self.gas_used.length()
+            + self.timestamp.length()
+            + self.extra_data.length()
+            + self.mix_hash.length()
+            + self.nonce.length();
+        self.for_each_optional_field(|field| length += field.length());
+        length
+    }
+
+    /// Calls the function for all present optional fields, in order, stopping at the first one
+    /// that is missing.
+    fn for_each_optional_field(&self, mut f: impl FnMut(&dyn Encodable)) {
+        let optional_fields: [Option<&dyn Encodable>; 6] = [
+            self.base_fee_per_gas.as_ref().map(|field| field as _),
+            self.withdrawals_root.as_ref().map(|field| field as _),
+            self.blob_gas_used.as_ref().map(|field| field as _),
+            self.excess_blob_gas.as_ref().map(|field| field as _),
+            self.parent_beacon_block_root
+                .as_ref()
+                .map(|field| field as _),
+            self.requests_root.as_ref().map(|field| field as _),
+        ];
+        optional_fields
+            .into_iter()
+            .map_while(|field| field)
+            .for_each(&mut f);
+    }
+}
+
+impl Encodable for ExecutionHeader {
+    fn encode(&self, out: &mut dyn BufMut) {
+        alloy_rlp::Header {
+            list: true,
+            payload_length: self.payload_length(),
+        }
+        .encode(out);
+        self.parent_hash.encode(out);
+        self.ommers_hash.encode(out);
+        self.beneficiary.encode(out);
+        self.state_root.encode(out);
+        self.transactions_root.encode(out);
+        self.receipts_root.encode(out);
+        self.logs_bloom.encode(out);
+        self.difficulty.encode(out);
+        self.number.encode(out);
+        self.gas_limit.encode(out);
+        self.gas_used.encode(out);
+        self.timestamp.encode(out);
+        self.extra_data.encode(out);
+        self.mix_hash.encode(out);
+        self.nonce.encode(out);
+        self.for_each_optional_field(|field| field.encode(out));
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for ExecutionHeader {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let mut payload = alloy_rlp::Header::decode_bytes(buf, /* is_list= */ true)?;
+        let payload = &mut payload;
+
+        /// Decodes the optional field, which is present only if there are remaining bytes.
+        fn decode_optional<T: Decodable>(payload: &mut &[u8]) -> alloy_rlp::Result<Option<T>> {
+            if payload.is_empty() {
+                Ok(None)
+            } else {
+                T::decode(payload).map(Some)
+            }
+        }
+
+        let header = Self {
+            parent_hash: Decodable::decode(payload)?,
+            ommers_hash: Decodable::decode(payload)?,
+            beneficiary: Decodable::decode(payload)?,
+            state_root: Decodable::decode(payload)?,
+            transactions_root: Decodable::decode(payload)?,
+            receipts_root: Decodable::decode(payload)?,
+            logs_bloom: Decodable::decode(payload)?,
+            difficulty: Decodable::decode(payload)?,
+            number: Decodable::decode(payload)?,
+            gas_limit: Decodable::decode(payload)?,
+            gas_used: Decodable::decode(payload)?,
+            timestamp: Decodable::decode(payload)?,
+            extra_data: Decodable::decode(payload)?,
+            mix_hash: Decodable::decode(payload)?,
+            nonce: Decodable::decode(payload)?,
+            base_fee_per_gas: decode_optional(payload)?,
+            withdrawals_root: decode_optional(payload)?,
+            blob_gas_used: decode_optional(payload)?,
+            excess_blob_gas: decode_optional(payload)?,
+            parent_beacon_block_root: decode_optional(payload)?,
+            requests_root: decode_optional(payload)?,
+        };
+        if !payload.is_empty() {
+            return Err(alloy_rlp::Error::UnexpectedLength);
+        }
+        Ok(header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use alloy_primitives::hex;
+
+    use crate::verkle::genesis_config::GenesisConfig;
+
+    use super::*;
+
+    fn read_genesis_header_rlp() -> anyhow::Result<Vec<u8>> {
+        let rlp = fs::read_to_string("../testdata/devnet6_genesis_header.hex")?;
+        Ok(hex::decode(rlp.trim())?)
+    }
+
+    #[test]
+    fn devnet6_genesis() -> anyhow::Result<()> {
+        let rlp = read_genesis_header_rlp()?;
+        let header = ExecutionHeader::decode_and_verify(&rlp, &GenesisConfig::DEVNET6_BLOCK_HASH)?;
+        assert_eq!(header.state_root, GenesisConfig::DEVNET6_STATE_ROOT);
+        assert_eq!(header.number, 0);
+        assert!(header.withdrawals_root.is_some());
+        assert!(header.blob_gas_used.is_none());
+        assert_eq!(header.hash(), GenesisConfig::DEVNET6_BLOCK_HASH);
+        assert_eq!(alloy_rlp::encode(&header), rlp);
+        Ok(())
+    }
+
+    #[test]
+    fn wrong_block_hash() -> anyhow::Result<()> {
+        let rlp = read_genesis_header_rlp()?;
+        assert!(matches!(
+            ExecutionHeader::decode_and_verify(&rlp, &B256::ZERO),
+            Err(HeaderError::WrongBlockHash { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_rlp() -> anyhow::Result<()> {
+        let rlp = read_genesis_header_rlp()?;
+        assert!(matches!(
+            ExecutionHeader::decode(&rlp[..rlp.len() - 1]),
+            Err(HeaderError::Decode(_))
+        ));
+
+        let mut with_trailing_bytes = rlp.clone();
+        with_trailing_bytes.extend([0x80, 0x80]);
+        assert!(matches!(
+            ExecutionHeader::decode(&with_trailing_bytes),
+            Err(HeaderError::TrailingBytes(2))
+        ));
+
+        // Optional fields that follow the missing one are not encoded
+        let mut header = ExecutionHeader::decode(&rlp)?;
+        header.withdrawals_root = None;
+        header.blob_gas_used = Some(0);
+        assert_eq!(
+            ExecutionHeader::decode(&alloy_rlp::encode(&header))?.blob_gas_used,
+            None
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub use error::HeaderError;
+pub use execution_header::ExecutionHeader;
+
+mod error;
+mod execution_header;
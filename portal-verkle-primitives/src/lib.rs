@@ -5,6 +5,7 @@ pub use trie_value::*;
 
 pub mod constants;
 mod ec;
+pub mod header;
 pub mod portal;
 pub mod proof;
 pub mod ssz;
@@ -13,3 +14,4 @@ mod trie_key;
 mod trie_value;
 pub(crate) mod utils;
 pub mod verkle;
+pub mod witness;
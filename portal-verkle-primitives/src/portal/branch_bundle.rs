@@ -11,7 +11,7 @@ use crate::{
     Point, ScalarField,
 };
 
-use super::NodeVerificationError;
+use super::{BranchFragmentNode, NodeVerificationError};
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct BranchBundleNodeWithProof {
@@ -37,15 +37,22 @@ impl BranchBundleNodeWithProof {
         }
 
         // 3. Verify multiproof
+        // The root node is already verified against the state root, so there is nothing to prove
+        // and the multiproof should be the one for the empty multiquery
+        if self.trie_path.is_empty() {
+            return if self.multiproof.is_empty() {
+                Ok(())
+            } else {
+                Err(NodeVerificationError::NonEmptyRootProof)
+            };
+        }
         let mut multi_query = VerifierMultiQuery::new();
         // Verify trie path
         multi_query.add_trie_path_proof(self.trie_path.clone(), commitment);
 
-        if self.multiproof.verify_portal_network_proof(multi_query) {
-            Ok(())
-        } else {
-            Err(NodeVerificationError::InvalidMultiPointProof)
-        }
+        self.multiproof
+            .verify_portal_network_proof(multi_query)
+            .map_err(NodeVerificationError::InvalidMultiPointProof)
     }
 }
 
@@ -92,11 +99,9 @@ impl BranchBundleNode {
                     .map(|index| (index, ScalarField::zero())),
             );
         }
-        if self.bundle_proof.verify_portal_network_proof(multiquery) {
-            Ok(())
-        } else {
-            Err(NodeVerificationError::InvalidBundleProof)
-        }
+        self.bundle_proof
+            .verify_portal_network_proof(multiquery)
+            .map_err(NodeVerificationError::InvalidBundleProof)
     }
 
     pub fn verify(&self, commitment: &Point) -> Result<(), NodeVerificationError> {
@@ -118,4 +123,13 @@ impl BranchBundleNode {
         self.verify_bundle_proof()?;
         Ok(())
     }
+
+    /// Verifies the node against the already verified parent, at the given child index.
+    pub fn verify_with_parent(
+        &self,
+        parent: &BranchFragmentNode,
+        child_index: u8,
+    ) -> Result<(), NodeVerificationError> {
+        self.verify(parent.child_commitment(child_index)?)
+    }
 }
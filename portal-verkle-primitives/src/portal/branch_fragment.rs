@@ -1,6 +1,7 @@
 use std::sync::OnceLock;
 
 use alloy_primitives::B256;
+use itertools::zip_eq;
 use ssz_derive::{Decode, Encode};
 
 use crate::{
@@ -11,7 +12,7 @@ use crate::{
     Point, ScalarField, CRS,
 };
 
-use super::NodeVerificationError;
+use super::{fragment_commitment, BranchBundleNode, NodeVerificationError};
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct BranchFragmentNodeWithProof {
@@ -44,24 +45,20 @@ impl BranchFragmentNodeWithProof {
         multi_query.add_trie_path_proof(self.trie_path.clone(), &self.bundle_commitment);
 
         // 3.2. Verify children openings to bundle commitment
+        let children_scalars = self.node.children_scalars();
         multi_query.add_for_commitment(
             &self.bundle_commitment,
             array_short(|fragment_child_index| {
                 (
                     branch_utils::child_index(self.node.fragment_index, fragment_child_index),
-                    self.node.children[fragment_child_index as usize]
-                        .as_ref()
-                        .map(Point::map_to_scalar_field)
-                        .unwrap_or_else(ScalarField::zero),
+                    children_scalars[fragment_child_index as usize].clone(),
                 )
             }),
         );
 
-        if self.multiproof.verify_portal_network_proof(multi_query) {
-            Ok(())
-        } else {
-            Err(NodeVerificationError::InvalidMultiPointProof)
-        }
+        self.multiproof
+            .verify_portal_network_proof(multi_query)
+            .map_err(NodeVerificationError::InvalidMultiPointProof)
     }
 }
 
@@ -95,18 +92,35 @@ impl BranchFragmentNode {
 
     pub fn commitment(&self) -> &Point {
         self.commitment.get_or_init(|| {
+            let children_scalars = self.children_scalars();
             self.children
                 .iter_enumerated_set_items()
-                .map(|(fragment_child_index, child)| {
+                .map(|(fragment_child_index, _)| {
                     CRS::commit_single(
                         branch_utils::child_index(self.fragment_index, fragment_child_index as u8),
-                        &child.map_to_scalar_field(),
+                        &children_scalars[fragment_child_index],
                     )
                 })
                 .sum()
         })
     }
 
+    /// Returns children mapped to scalar field (zero for missing children).
+    fn children_scalars(&self) -> [ScalarField; PORTAL_NETWORK_NODE_WIDTH] {
+        let (indices, children): (Vec<_>, Vec<_>) = self
+            .children
+            .iter_enumerated_set_items()
+            .map(|(fragment_child_index, child)| (fragment_child_index, child.clone()))
+            .unzip();
+        let mut result = array_short(|_| ScalarField::zero());
+        for (fragment_child_index, scalar) in
+            zip_eq(indices, Point::batch_map_to_scalar_field(&children))
+        {
+            result[fragment_child_index] = scalar;
+        }
+        result
+    }
+
     pub fn verify(&self, commitment: &Point) -> Result<(), NodeVerificationError> {
         if commitment != self.commitment() {
             return Err(NodeVerificationError::new_wrong_commitment(
@@ -127,4 +141,27 @@ impl BranchFragmentNode {
         }
         Ok(())
     }
+
+    /// Verifies the node against the already verified parent.
+    pub fn verify_with_parent(
+        &self,
+        parent: &BranchBundleNode,
+    ) -> Result<(), NodeVerificationError> {
+        self.verify(fragment_commitment(
+            parent.fragments(),
+            self.fragment_index,
+        )?)
+    }
+
+    /// Returns the commitment of the child at the given index (of the branch node).
+    ///
+    /// Returns error if child is not part of this fragment or if it's not present.
+    pub fn child_commitment(&self, child_index: u8) -> Result<&Point, NodeVerificationError> {
+        if branch_utils::fragment_index(child_index) != self.fragment_index {
+            return Err(NodeVerificationError::MissingChild(child_index));
+        }
+        self.children[branch_utils::fragment_child_index(child_index) as usize]
+            .as_ref()
+            .ok_or(NodeVerificationError::MissingChild(child_index))
+    }
 }
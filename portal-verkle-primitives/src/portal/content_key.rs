@@ -0,0 +1,361 @@
+use alloy_primitives::{B256, U256};
+use sha2::{Digest, Sha256};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+
+use crate::{ssz::TriePath, Point, Stem};
+
+use super::{ContentValidationError, PortalVerkleNode, PortalVerkleNodeWithProof};
+
+/// The key of the branch node (either bundle or fragment) in the Portal Network.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct BranchNodeKey {
+    /// The path from the root to the branch (bundle) node.
+    pub path: TriePath,
+    pub commitment: Point,
+}
+
+/// The key of the leaf node (either bundle or fragment) in the Portal Network.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct LeafNodeKey {
+    pub stem: Stem,
+    pub commitment: Point,
+}
+
+/// The content key of the verkle node in the Portal Network.
+///
+/// The same key is used for the node with proof (used when offering content) and for the node
+/// without proof (used when retrieving content).
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[ssz(enum_behaviour = "union")]
+pub enum PortalVerkleContentKey {
+    BranchBundle(BranchNodeKey),
+    BranchFragment(BranchNodeKey),
+    LeafBundle(LeafNodeKey),
+    LeafFragment(LeafNodeKey),
+}
+
+impl PortalVerkleContentKey {
+    pub fn commitment(&self) -> &Point {
+        match self {
+            Self::BranchBundle(key) | Self::BranchFragment(key) => &key.commitment,
+            Self::LeafBundle(key) | Self::LeafFragment(key) => &key.commitment,
+        }
+    }
+
+    /// Returns the content id, which is SHA-256 hash of the SSZ encoded content key.
+    pub fn content_id(&self) -> B256 {
+        B256::from_slice(&Sha256::digest(self.as_ssz_bytes()))
+    }
+
+    /// Returns the distance between the content id and the given node id.
+    pub fn distance(&self, node_id: &B256) -> U256 {
+        distance(&self.content_id(), node_id)
+    }
+}
+
+/// Validates the content (node with proof), as received when content is offered.
+///
+/// The content is decoded and verified against the commitment, trie path and stem from the
+/// content key.
+pub fn validate(
+    content_key: &PortalVerkleContentKey,
+    content_bytes: &[u8],
+    state_root: &B256,
+) -> Result<PortalVerkleNodeWithProof, ContentValidationError> {
+    let content = PortalVerkleNodeWithProof::from_ssz_bytes(content_bytes)?;
+    verify_content(content_key, &content, state_root)?;
+    Ok(content)
+}
+
+/// Verifies the decoded content (node with proof) against the content key and the state root.
+pub(super) fn verify_content(
+    content_key: &PortalVerkleContentKey,
+    content: &PortalVerkleNodeWithProof,
+    state_root: &B256,
+) -> Result<(), ContentValidationError> {
+    let commitment = content_key.commitment();
+    match (content_key, content) {
+        (
+            PortalVerkleContentKey::BranchBundle(key),
+            PortalVerkleNodeWithProof::BranchBundle(node),
+        ) => {
+            check_trie_path(&key.path, node.trie_path.iter().map(|(_, index)| *index))?;
+            node.verify(commitment, state_root)?;
+        }
+        (
+            PortalVerkleContentKey::BranchFragment(key),
+            PortalVerkleNodeWithProof::BranchFragment(node),
+        ) => {
+            check_trie_path(&key.path, node.trie_path.iter().map(|(_, index)| *index))?;
+            node.verify(commitment, state_root)?;
+        }
+        (PortalVerkleContentKey::LeafBundle(key), PortalVerkleNodeWithProof::LeafBundle(node)) => {
+            check_stem(&key.stem, node.node.stem())?;
+            node.verify(commitment, state_root)?;
+        }
+        (
+            PortalVerkleContentKey::LeafFragment(key),
+            PortalVerkleNodeWithProof::LeafFragment(node),
+        ) => {
+            node.verify(commitment, state_root, &key.stem)?;
+        }
+        _ => return Err(ContentValidationError::WrongContentType),
+    }
+    Ok(())
+}
+
+/// Validates the content (node without proof), as received when content is retrieved.
+///
+/// The content is decoded and verified against the commitment from the content key.
+pub fn validate_node(
+    content_key: &PortalVerkleContentKey,
+    content_bytes: &[u8],
+) -> Result<PortalVerkleNode, ContentValidationError> {
+    let content = PortalVerkleNode::from_ssz_bytes(content_bytes)?;
+    match (content_key, &content) {
+        (PortalVerkleContentKey::BranchBundle(_), PortalVerkleNode::BranchBundle(_))
+        | (PortalVerkleContentKey::BranchFragment(_), PortalVerkleNode::BranchFragment(_))
+        | (PortalVerkleContentKey::LeafFragment(_), PortalVerkleNode::LeafFragment(_)) => {}
+        (PortalVerkleContentKey::LeafBundle(key), PortalVerkleNode::LeafBundle(node)) => {
+            check_stem(&key.stem, node.stem())?
+        }
+        _ => return Err(ContentValidationError::WrongContentType),
+    }
+    content.verify(content_key.commitment())?;
+    Ok(content)
+}
+
+/// Returns the XOR distance between two ids (content or node).
+pub fn distance(a: &B256, b: &B256) -> U256 {
+    U256::from_be_bytes(a.0) ^ U256::from_be_bytes(b.0)
+}
+
+fn check_trie_path(
+    expected: &TriePath,
+    actual: impl Iterator<Item = u8>,
+) -> Result<(), ContentValidationError> {
+    if expected.iter().copied().eq(actual) {
+        Ok(())
+    } else {
+        Err(ContentValidationError::WrongTriePath)
+    }
+}
+
+fn check_stem(expected: &Stem, actual: &Stem) -> Result<(), ContentValidationError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(ContentValidationError::WrongStem {
+            expected: *expected,
+            actual: *actual,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use alloy_primitives::b256;
+
+    use crate::{
+        portal::NodeVerificationError,
+        proof::{MultiProof, ProofError},
+        verkle::{
+            genesis_config::GenesisConfig,
+            nodes::{
+                portal_branch_node_builder::PortalBranchNodeBuilder,
+                portal_leaf_node_builder::PortalLeafNodeBuilder,
+            },
+            VerkleTrie,
+        },
+    };
+
+    use super::*;
+
+    /// Returns the genesis trie and one of its stems.
+    fn create_trie() -> anyhow::Result<(VerkleTrie, Stem)> {
+        let reader = BufReader::new(File::open("../testdata/genesis.json")?);
+        let genesis_config: GenesisConfig = serde_json::from_reader(reader)?;
+        let state_writes = genesis_config.into_state_writes();
+        let mut trie = VerkleTrie::new();
+        trie.update(&state_writes)?;
+        Ok((trie, state_writes[0].stem))
+    }
+
+    #[test]
+    fn validate_branch() -> anyhow::Result<()> {
+        let (trie, stem) = create_trie()?;
+        let path_to_leaf = trie.traverse_to_leaf(&stem)?;
+        let (branch_node, _) = path_to_leaf.trie_path[1];
+        let builder = PortalBranchNodeBuilder::new(branch_node, &path_to_leaf.trie_path[..1])
+            .expect("trie path should match depth");
+        let fragment_index = (0..16)
+            .find(|fragment_index| !builder.fragment_commitment(*fragment_index).is_zero())
+            .unwrap();
+
+        let key = BranchNodeKey {
+            path: TriePath::from(stem[..1].to_vec()),
+            commitment: branch_node.commitment().to_point(),
+        };
+        let bundle_key = PortalVerkleContentKey::BranchBundle(key.clone());
+        let bundle = PortalVerkleNodeWithProof::BranchBundle(
+            builder.bundle_node_with_proof(GenesisConfig::DEVNET6_BLOCK_HASH)?,
+        );
+        let content_bytes = bundle.as_ssz_bytes();
+        assert_eq!(
+            validate(&bundle_key, &content_bytes, &trie.root())?.as_ssz_bytes(),
+            bundle.as_ssz_bytes()
+        );
+        let node_bytes = bundle.into_node().as_ssz_bytes();
+        assert_eq!(
+            validate_node(&bundle_key, &node_bytes)?.as_ssz_bytes(),
+            node_bytes
+        );
+
+        // Wrong path
+        let wrong_path_key = PortalVerkleContentKey::BranchBundle(BranchNodeKey {
+            path: TriePath::from(vec![stem[0].wrapping_add(1)]),
+            ..key.clone()
+        });
+        assert!(matches!(
+            validate(&wrong_path_key, &content_bytes, &trie.root()),
+            Err(ContentValidationError::WrongTriePath)
+        ));
+
+        // Wrong content type
+        let fragment_key = PortalVerkleContentKey::BranchFragment(BranchNodeKey {
+            commitment: builder.fragment_commitment(fragment_index).clone(),
+            ..key
+        });
+        assert!(matches!(
+            validate(&fragment_key, &content_bytes, &trie.root()),
+            Err(ContentValidationError::WrongContentType)
+        ));
+
+        let fragment = PortalVerkleNodeWithProof::BranchFragment(
+            builder.fragment_node_with_proof(fragment_index, GenesisConfig::DEVNET6_BLOCK_HASH)?,
+        );
+        assert_eq!(
+            validate(&fragment_key, &fragment.as_ssz_bytes(), &trie.root())?.as_ssz_bytes(),
+            fragment.as_ssz_bytes()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validate_root_branch() -> anyhow::Result<()> {
+        let (trie, stem) = create_trie()?;
+        let path_to_leaf = trie.traverse_to_leaf(&stem)?;
+        let builder = PortalBranchNodeBuilder::new(path_to_leaf.trie_path[0].0, &[])
+            .expect("trie path should match depth");
+
+        let key = PortalVerkleContentKey::BranchBundle(BranchNodeKey {
+            path: TriePath::default(),
+            commitment: trie.root_commitment().clone(),
+        });
+        let mut bundle = builder.bundle_node_with_proof(GenesisConfig::DEVNET6_BLOCK_HASH)?;
+        let content = PortalVerkleNodeWithProof::BranchBundle(bundle.clone());
+        assert_eq!(
+            validate(&key, &content.as_ssz_bytes(), &trie.root())?.as_ssz_bytes(),
+            content.as_ssz_bytes()
+        );
+
+        // Non-empty multiproof
+        bundle.multiproof = MultiProof::clone(bundle.node.bundle_proof());
+        let content = PortalVerkleNodeWithProof::BranchBundle(bundle);
+        assert!(matches!(
+            validate(&key, &content.as_ssz_bytes(), &trie.root()),
+            Err(ContentValidationError::Verification(
+                NodeVerificationError::NonEmptyRootProof
+            ))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_leaf() -> anyhow::Result<()> {
+        let (trie, stem) = create_trie()?;
+        let path_to_leaf = trie.traverse_to_leaf(&stem)?;
+        let builder = PortalLeafNodeBuilder::new(&path_to_leaf);
+
+        let bundle_key = PortalVerkleContentKey::LeafBundle(LeafNodeKey {
+            stem,
+            commitment: builder.bundle_commitment().clone(),
+        });
+        let bundle = PortalVerkleNodeWithProof::LeafBundle(
+            builder.bundle_node_with_proof(GenesisConfig::DEVNET6_BLOCK_HASH)?,
+        );
+        assert_eq!(
+            validate(&bundle_key, &bundle.as_ssz_bytes(), &trie.root())?.as_ssz_bytes(),
+            bundle.as_ssz_bytes()
+        );
+
+        let fragment_key = PortalVerkleContentKey::LeafFragment(LeafNodeKey {
+            stem,
+            commitment: builder.fragment_commitment(0).clone(),
+        });
+        let fragment = PortalVerkleNodeWithProof::LeafFragment(
+            builder.fragment_node_with_proof(0, GenesisConfig::DEVNET6_BLOCK_HASH)?,
+        );
+        let content_bytes = fragment.as_ssz_bytes();
+        assert_eq!(
+            validate(&fragment_key, &content_bytes, &trie.root())?.as_ssz_bytes(),
+            fragment.as_ssz_bytes()
+        );
+
+        // Wrong stem
+        let mut wrong_stem = stem;
+        wrong_stem[30] ^= 1;
+        let wrong_stem_key = PortalVerkleContentKey::LeafFragment(LeafNodeKey {
+            stem: wrong_stem,
+            commitment: builder.fragment_commitment(0).clone(),
+        });
+        assert!(matches!(
+            validate(&wrong_stem_key, &content_bytes, &trie.root()),
+            Err(ContentValidationError::Verification(
+                NodeVerificationError::InvalidMultiPointProof(ProofError::VerificationFailed)
+            ))
+        ));
+
+        // Wrong state root
+        assert!(matches!(
+            validate(&fragment_key, &content_bytes, &B256::ZERO),
+            Err(ContentValidationError::Verification(
+                NodeVerificationError::WrongRoot { .. }
+            ))
+        ));
+
+        // Invalid bytes
+        assert!(matches!(
+            validate(&fragment_key, &content_bytes[1..], &trie.root()),
+            Err(ContentValidationError::Decode(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn content_id() -> anyhow::Result<()> {
+        let key = PortalVerkleContentKey::LeafBundle(LeafNodeKey {
+            stem: Stem::repeat_byte(1),
+            commitment: Point::try_from(&GenesisConfig::DEVNET6_STATE_ROOT)?,
+        });
+        let content_id = key.content_id();
+        assert_eq!(
+            content_id,
+            B256::from_slice(&Sha256::digest(key.as_ssz_bytes()))
+        );
+        assert_eq!(key.distance(&content_id), U256::ZERO);
+        Ok(())
+    }
+
+    #[test]
+    fn xor_distance() {
+        let a = b256!("0000000000000000000000000000000000000000000000000000000000000005");
+        let b = b256!("8000000000000000000000000000000000000000000000000000000000000003");
+        assert_eq!(distance(&a, &a), U256::ZERO);
+        assert_eq!(distance(&a, &b), distance(&b, &a));
+        assert_eq!(distance(&a, &b), (U256::from(1) << 255) + U256::from(6));
+    }
+}
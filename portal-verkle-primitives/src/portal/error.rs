@@ -1,7 +1,9 @@
 use alloy_primitives::B256;
 use thiserror::Error;
 
-use crate::Point;
+use crate::{proof::ProofError, ssz::TriePath, Point, Stem};
+
+use super::PortalVerkleContentKey;
 
 #[derive(Debug, Error)]
 pub enum NodeVerificationError {
@@ -26,14 +28,20 @@ pub enum NodeVerificationError {
     #[error("Child's value is zero")]
     ZeroChild,
 
-    #[error("Bundle proof is invalid")]
-    InvalidBundleProof,
+    #[error("Bundle proof is invalid: {0}")]
+    InvalidBundleProof(#[source] ProofError),
+
+    #[error("MultiPointProof is invalid: {0}")]
+    InvalidMultiPointProof(#[source] ProofError),
 
-    #[error("MultiPointProof is invalid")]
-    InvalidMultiPointProof,
+    #[error("MultiPointProof of the root node is not empty")]
+    NonEmptyRootProof,
 
     #[error("Invalid fragment index: {0}")]
     InvalidFragmentIndex(u8),
+
+    #[error("Parent node doesn't have child at index: {0}")]
+    MissingChild(u8),
 }
 
 impl NodeVerificationError {
@@ -50,3 +58,60 @@ impl NodeVerificationError {
         }
     }
 }
+
+#[derive(Debug, Error)]
+pub enum ContentValidationError {
+    #[error("Failed to decode content: {0:?}")]
+    Decode(ssz::DecodeError),
+
+    #[error("Content doesn't match the type of the content key")]
+    WrongContentType,
+
+    #[error("Trie path doesn't match the one from the content key")]
+    WrongTriePath,
+
+    #[error("Stem doesn't match. expected: {expected} actual: {actual}")]
+    WrongStem { expected: Stem, actual: Stem },
+
+    #[error("Header of the block {0} is not trusted")]
+    UnknownBlockHash(B256),
+
+    #[error(transparent)]
+    Verification(#[from] NodeVerificationError),
+}
+
+impl From<ssz::DecodeError> for ContentValidationError {
+    fn from(err: ssz::DecodeError) -> Self {
+        Self::Decode(err)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PortalStateReaderError {
+    #[error("Failed to fetch node {key:?}: {source}")]
+    Fetch {
+        key: Box<PortalVerkleContentKey>,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("Failed to fetch the stem of the leaf node at {path:?}: {source}")]
+    FetchLeafStem {
+        path: TriePath,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("Node {0:?} is not available")]
+    MissingNode(Box<PortalVerkleContentKey>),
+
+    #[error("Leaf stem {stem:?} doesn't start with its path {path:?}")]
+    WrongLeafStem { path: TriePath, stem: Stem },
+
+    #[error("Branch node on the path to stem {0:?} is at the maximum depth")]
+    BranchAtMaxDepth(Stem),
+
+    #[error("Node {key:?} is invalid: {source}")]
+    InvalidNode {
+        key: Box<PortalVerkleContentKey>,
+        source: ContentValidationError,
+    },
+}
@@ -0,0 +1,77 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    convert::Infallible,
+    error::Error,
+};
+
+use alloy_primitives::B256;
+use ssz::Encode;
+
+use crate::{Point, Stem};
+
+use super::{LeafNodeKey, PortalVerkleContentKey, PortalVerkleNode};
+
+/// The source of the Portal Network nodes (e.g. the Portal Network client).
+pub trait NodeFetcher {
+    type Error: Error + Send + Sync + 'static;
+
+    /// Returns the SSZ encoded node (without proof) for the content key, or `None` if it's not
+    /// available.
+    fn fetch(&self, content_key: &PortalVerkleContentKey) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Returns the stem of the leaf node at the given path (with the given commitment), or `None`
+    /// if the node at that path is a branch node.
+    ///
+    /// Branch nodes only contain commitments of their children, so the stem of the leaf node,
+    /// which is needed for its content key, has to be provided by the fetcher. The stem isn't
+    /// trusted: the leaf bundle node is fetched with it and verified against the commitment.
+    fn leaf_stem(&self, path: &[u8], commitment: &Point) -> Result<Option<Stem>, Self::Error>;
+}
+
+/// The [NodeFetcher] that keeps all nodes in memory.
+#[derive(Default)]
+pub struct MemoryNodeFetcher {
+    /// The content, by content id.
+    content: HashMap<B256, Vec<u8>>,
+    /// The stems of all inserted leaf nodes.
+    leaf_stems: BTreeSet<Stem>,
+}
+
+impl MemoryNodeFetcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, content_key: &PortalVerkleContentKey, node: &PortalVerkleNode) {
+        if let PortalVerkleContentKey::LeafBundle(key) = content_key {
+            self.leaf_stems.insert(key.stem);
+        }
+        self.content
+            .insert(content_key.content_id(), node.as_ssz_bytes());
+    }
+}
+
+impl NodeFetcher for MemoryNodeFetcher {
+    type Error = Infallible;
+
+    fn fetch(&self, content_key: &PortalVerkleContentKey) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.content.get(&content_key.content_id()).cloned())
+    }
+
+    fn leaf_stem(&self, path: &[u8], commitment: &Point) -> Result<Option<Stem>, Self::Error> {
+        let mut start = Stem::ZERO;
+        start[..path.len()].copy_from_slice(path);
+        let stem = self
+            .leaf_stems
+            .range(start..)
+            .take_while(|stem| stem.starts_with(path))
+            .find(|stem| {
+                let content_key = PortalVerkleContentKey::LeafBundle(LeafNodeKey {
+                    stem: **stem,
+                    commitment: commitment.clone(),
+                });
+                self.content.contains_key(&content_key.content_id())
+            });
+        Ok(stem.copied())
+    }
+}
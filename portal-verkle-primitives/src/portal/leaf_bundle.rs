@@ -13,7 +13,7 @@ use crate::{
     Point, ScalarField, Stem, CRS,
 };
 
-use super::NodeVerificationError;
+use super::{BranchFragmentNode, NodeVerificationError};
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct LeafBundleNodeWithProof {
@@ -43,11 +43,9 @@ impl LeafBundleNodeWithProof {
         // Verify trie path
         multi_query.add_trie_path_proof(self.trie_path.zip_with_stem(self.node.stem()), commitment);
 
-        if self.multiproof.verify_portal_network_proof(multi_query) {
-            Ok(())
-        } else {
-            Err(NodeVerificationError::InvalidMultiPointProof)
-        }
+        self.multiproof
+            .verify_portal_network_proof(multi_query)
+            .map_err(NodeVerificationError::InvalidMultiPointProof)
     }
 }
 
@@ -95,12 +93,14 @@ impl LeafBundleNode {
 
     pub fn commitment(&self) -> &Point {
         self.commitment.get_or_init(|| {
-            let [c1, c2] = self.c1_c2();
+            let [c1, c2] = Point::batch_map_to_scalar_field(&self.c1_c2())
+                .try_into()
+                .expect("should have scalars for c1 and c2");
             CRS::commit_sparse(&[
                 (LEAF_MARKER_INDEX, ScalarField::from(self.marker)),
                 (LEAF_STEM_INDEX, ScalarField::from(&self.stem)),
-                (LEAF_C1_INDEX, c1.map_to_scalar_field()),
-                (LEAF_C2_INDEX, c2.map_to_scalar_field()),
+                (LEAF_C1_INDEX, c1),
+                (LEAF_C2_INDEX, c2),
             ])
         })
     }
@@ -121,11 +121,9 @@ impl LeafBundleNode {
                     .map(|index| (index, ScalarField::zero())),
             );
         }
-        if self.bundle_proof.verify_portal_network_proof(multiquery) {
-            Ok(())
-        } else {
-            Err(NodeVerificationError::InvalidBundleProof)
-        }
+        self.bundle_proof
+            .verify_portal_network_proof(multiquery)
+            .map_err(NodeVerificationError::InvalidBundleProof)
     }
 
     pub fn verify(&self, commitment: &Point) -> Result<(), NodeVerificationError> {
@@ -147,4 +145,13 @@ impl LeafBundleNode {
         self.verify_bundle_proof()?;
         Ok(())
     }
+
+    /// Verifies the node against the already verified parent, at the given child index.
+    pub fn verify_with_parent(
+        &self,
+        parent: &BranchFragmentNode,
+        child_index: u8,
+    ) -> Result<(), NodeVerificationError> {
+        self.verify(parent.child_commitment(child_index)?)
+    }
 }
@@ -11,7 +11,7 @@ use crate::{
     Point, Stem, TrieValue, TrieValueSplit, CRS,
 };
 
-use super::NodeVerificationError;
+use super::{fragment_commitment, LeafBundleNode, NodeVerificationError};
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
 pub struct LeafFragmentNodeWithProof {
@@ -29,6 +29,10 @@ pub struct LeafFragmentNodeWithProof {
 }
 
 impl LeafFragmentNodeWithProof {
+    /// Verifies the node and its proof.
+    ///
+    /// The stem of the leaf is not part of the node, and it should be taken from the content key
+    /// (see [PortalVerkleContentKey](super::PortalVerkleContentKey)).
     pub fn verify(
         &self,
         commitment: &Point,
@@ -77,11 +81,9 @@ impl LeafFragmentNodeWithProof {
             .flatten(),
         );
 
-        if self.multiproof.verify_portal_network_proof(multi_query) {
-            Ok(())
-        } else {
-            Err(NodeVerificationError::InvalidMultiPointProof)
-        }
+        self.multiproof
+            .verify_portal_network_proof(multi_query)
+            .map_err(NodeVerificationError::InvalidMultiPointProof)
     }
 }
 
@@ -144,4 +146,14 @@ impl LeafFragmentNode {
         }
         Ok(())
     }
+
+    /// Verifies the node against the already verified parent.
+    ///
+    /// The fragment commitments of the parent are already verified to add up to its c1 and c2.
+    pub fn verify_with_parent(&self, parent: &LeafBundleNode) -> Result<(), NodeVerificationError> {
+        self.verify(fragment_commitment(
+            parent.fragments(),
+            self.fragment_index,
+        )?)
+    }
 }
@@ -1,13 +1,36 @@
+use crate::{constants::PORTAL_NETWORK_NODE_WIDTH, ssz::SparseVector, Point};
+
 pub use branch_bundle::{BranchBundleNode, BranchBundleNodeWithProof};
 pub use branch_fragment::{BranchFragmentNode, BranchFragmentNodeWithProof};
-pub use error::NodeVerificationError;
+pub use content_key::{
+    distance, validate, validate_node, BranchNodeKey, LeafNodeKey, PortalVerkleContentKey,
+};
+pub use error::{ContentValidationError, NodeVerificationError, PortalStateReaderError};
+pub use fetcher::{MemoryNodeFetcher, NodeFetcher};
 pub use leaf_bundle::{LeafBundleNode, LeafBundleNodeWithProof};
 pub use leaf_fragment::{LeafFragmentNode, LeafFragmentNodeWithProof};
 pub use node::{PortalVerkleNode, PortalVerkleNodeWithProof};
+pub use state_reader::PortalStateReader;
+pub use trusted_headers::TrustedHeaders;
 
 mod branch_bundle;
 mod branch_fragment;
+mod content_key;
 mod error;
+mod fetcher;
 mod leaf_bundle;
 mod leaf_fragment;
 mod node;
+mod state_reader;
+mod trusted_headers;
+
+/// Returns the commitment of the fragment from its (already verified) bundle node.
+fn fragment_commitment(
+    fragments: &SparseVector<Point, PORTAL_NETWORK_NODE_WIDTH>,
+    fragment_index: u8,
+) -> Result<&Point, NodeVerificationError> {
+    fragments
+        .get(fragment_index as usize)
+        .and_then(Option::as_ref)
+        .ok_or(NodeVerificationError::MissingChild(fragment_index))
+}
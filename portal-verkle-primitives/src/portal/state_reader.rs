@@ -0,0 +1,436 @@
+use crate::{
+    constants::PORTAL_NETWORK_NODE_WIDTH, ssz::TriePath, utils::branch_utils, Point, Stem, TrieKey,
+    TrieValue,
+};
+
+use super::{
+    validate_node, BranchBundleNode, BranchFragmentNode, BranchNodeKey, LeafBundleNode,
+    LeafFragmentNode, LeafNodeKey, NodeFetcher, NodeVerificationError, PortalStateReaderError,
+    PortalVerkleContentKey, PortalVerkleNode,
+};
+
+/// Reads the state from the Portal Network nodes, starting from the trusted root commitment.
+///
+/// Each node is fetched with the commitment from its parent (the root node with the trusted root
+/// commitment) and verified against it, so the returned values are proven by the root.
+pub struct PortalStateReader<F> {
+    fetcher: F,
+    root: Point,
+}
+
+impl<F: NodeFetcher> PortalStateReader<F> {
+    pub fn new(fetcher: F, root: Point) -> Self {
+        Self { fetcher, root }
+    }
+
+    pub fn fetcher(&self) -> &F {
+        &self.fetcher
+    }
+
+    pub fn root(&self) -> &Point {
+        &self.root
+    }
+
+    /// Returns the value of the key, or `None` if it's proven that the key is not present.
+    ///
+    /// Whether the child of the branch node is a branch or a leaf node can't be known from its
+    /// parent, so it's determined by the depth (only leaf nodes are at the maximum depth) or by
+    /// the [NodeFetcher::leaf_stem].
+    pub fn get(&self, key: &TrieKey) -> Result<Option<TrieValue>, PortalStateReaderError> {
+        let stem = key.stem();
+        let mut bundle = self.fetch_branch_bundle(TriePath::default(), self.root.clone())?;
+        for depth in 0..Stem::len_bytes() {
+            let path = TriePath::from(stem[..depth].to_vec());
+            let child_index = stem[depth];
+
+            let fragment_index = branch_utils::fragment_index(child_index);
+            let Some(fragment_commitment) = bundle.fragments()[fragment_index as usize].clone()
+            else {
+                return Ok(None);
+            };
+            let fragment = self.fetch_branch_fragment(path, fragment_commitment, fragment_index)?;
+
+            let fragment_child_index = branch_utils::fragment_child_index(child_index);
+            let Some(child_commitment) = fragment.children()[fragment_child_index as usize].clone()
+            else {
+                return Ok(None);
+            };
+
+            let child_path = &stem[..=depth];
+            if let Some(leaf_stem) = self.leaf_stem(child_path, &child_commitment)? {
+                return self.get_from_leaf(key, leaf_stem, child_commitment);
+            }
+            bundle =
+                self.fetch_branch_bundle(TriePath::from(child_path.to_vec()), child_commitment)?;
+        }
+        Err(PortalStateReaderError::BranchAtMaxDepth(stem))
+    }
+
+    /// Returns the stem of the leaf node at the given path, or `None` if it's a branch node.
+    fn leaf_stem(
+        &self,
+        path: &[u8],
+        commitment: &Point,
+    ) -> Result<Option<Stem>, PortalStateReaderError> {
+        if path.len() == Stem::len_bytes() {
+            return Ok(Some(Stem::from_slice(path)));
+        }
+        let leaf_stem = self.fetcher.leaf_stem(path, commitment).map_err(|err| {
+            PortalStateReaderError::FetchLeafStem {
+                path: TriePath::from(path.to_vec()),
+                source: err.into(),
+            }
+        })?;
+        match leaf_stem {
+            Some(leaf_stem) if !leaf_stem.starts_with(path) => {
+                Err(PortalStateReaderError::WrongLeafStem {
+                    path: TriePath::from(path.to_vec()),
+                    stem: leaf_stem,
+                })
+            }
+            leaf_stem => Ok(leaf_stem),
+        }
+    }
+
+    /// Returns the value of the key from the leaf node with the given stem.
+    ///
+    /// If the leaf node has different stem, the key is not present.
+    fn get_from_leaf(
+        &self,
+        key: &TrieKey,
+        stem: Stem,
+        commitment: Point,
+    ) -> Result<Option<TrieValue>, PortalStateReaderError> {
+        let bundle = self.fetch_leaf_bundle(stem, commitment)?;
+        if bundle.stem() != &key.stem() {
+            return Ok(None);
+        }
+
+        let fragment_index = key.suffix() / PORTAL_NETWORK_NODE_WIDTH as u8;
+        let Some(fragment_commitment) = bundle.fragments()[fragment_index as usize].clone() else {
+            return Ok(None);
+        };
+        let fragment = self.fetch_leaf_fragment(stem, fragment_commitment, fragment_index)?;
+        let fragment_child_index = key.suffix() % PORTAL_NETWORK_NODE_WIDTH as u8;
+        Ok(fragment.children()[fragment_child_index as usize])
+    }
+
+    fn fetch_leaf_bundle(
+        &self,
+        stem: Stem,
+        commitment: Point,
+    ) -> Result<LeafBundleNode, PortalStateReaderError> {
+        let content_key = PortalVerkleContentKey::LeafBundle(LeafNodeKey { stem, commitment });
+        match self.fetch(&content_key)? {
+            Some(PortalVerkleNode::LeafBundle(bundle)) => Ok(bundle),
+            Some(_) => unreachable!("Content type should be validated"),
+            None => Err(PortalStateReaderError::MissingNode(content_key.into())),
+        }
+    }
+
+    fn fetch_branch_bundle(
+        &self,
+        path: TriePath,
+        commitment: Point,
+    ) -> Result<BranchBundleNode, PortalStateReaderError> {
+        let content_key = PortalVerkleContentKey::BranchBundle(BranchNodeKey { path, commitment });
+        match self.fetch(&content_key)? {
+            Some(PortalVerkleNode::BranchBundle(bundle)) => Ok(bundle),
+            Some(_) => unreachable!("Content type should be validated"),
+            None => Err(PortalStateReaderError::MissingNode(content_key.into())),
+        }
+    }
+
+    fn fetch_branch_fragment(
+        &self,
+        path: TriePath,
+        commitment: Point,
+        fragment_index: u8,
+    ) -> Result<BranchFragmentNode, PortalStateReaderError> {
+        let content_key =
+            PortalVerkleContentKey::BranchFragment(BranchNodeKey { path, commitment });
+        let fragment = match self.fetch(&content_key)? {
+            Some(PortalVerkleNode::BranchFragment(fragment)) => fragment,
+            Some(_) => unreachable!("Content type should be validated"),
+            None => return Err(PortalStateReaderError::MissingNode(content_key.into())),
+        };
+        check_fragment_index(&content_key, fragment_index, fragment.fragment_index())?;
+        Ok(fragment)
+    }
+
+    fn fetch_leaf_fragment(
+        &self,
+        stem: Stem,
+        commitment: Point,
+        fragment_index: u8,
+    ) -> Result<LeafFragmentNode, PortalStateReaderError> {
+        let content_key = PortalVerkleContentKey::LeafFragment(LeafNodeKey { stem, commitment });
+        let fragment = match self.fetch(&content_key)? {
+            Some(PortalVerkleNode::LeafFragment(fragment)) => fragment,
+            Some(_) => unreachable!("Content type should be validated"),
+            None => return Err(PortalStateReaderError::MissingNode(content_key.into())),
+        };
+        check_fragment_index(&content_key, fragment_index, fragment.fragment_index())?;
+        Ok(fragment)
+    }
+
+    /// Fetches and validates the node.
+    fn fetch(
+        &self,
+        content_key: &PortalVerkleContentKey,
+    ) -> Result<Option<PortalVerkleNode>, PortalStateReaderError> {
+        let content_bytes =
+            self.fetcher
+                .fetch(content_key)
+                .map_err(|err| PortalStateReaderError::Fetch {
+                    key: content_key.clone().into(),
+                    source: err.into(),
+                })?;
+        let Some(content_bytes) = content_bytes else {
+            return Ok(None);
+        };
+        validate_node(content_key, &content_bytes)
+            .map(Some)
+            .map_err(|err| PortalStateReaderError::InvalidNode {
+                key: content_key.clone().into(),
+                source: err,
+            })
+    }
+}
+
+fn check_fragment_index(
+    content_key: &PortalVerkleContentKey,
+    expected: u8,
+    actual: u8,
+) -> Result<(), PortalStateReaderError> {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(PortalStateReaderError::InvalidNode {
+            key: content_key.clone().into(),
+            source: NodeVerificationError::InvalidFragmentIndex(actual).into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use crate::{
+        portal::{ContentValidationError, MemoryNodeFetcher},
+        verkle::{
+            genesis_config::GenesisConfig,
+            nodes::{
+                portal_branch_node_builder::PortalBranchNodeBuilder,
+                portal_leaf_node_builder::PortalLeafNodeBuilder,
+            },
+            StateWrites, VerkleTrie,
+        },
+    };
+
+    use super::*;
+
+    fn create_trie() -> anyhow::Result<(VerkleTrie, StateWrites)> {
+        let reader = BufReader::new(File::open("../testdata/genesis.json")?);
+        let genesis_config: GenesisConfig = serde_json::from_reader(reader)?;
+        let state_writes = genesis_config.into_state_writes();
+        let mut trie = VerkleTrie::new();
+        trie.update(&state_writes)?;
+        Ok((trie, state_writes))
+    }
+
+    /// Adds all nodes that are needed for reading the values of the stem.
+    fn add_nodes(
+        fetcher: &mut MemoryNodeFetcher,
+        trie: &VerkleTrie,
+        stem: &Stem,
+    ) -> anyhow::Result<()> {
+        let path_to_leaf = trie.traverse_to_leaf(stem)?;
+        for (depth, (branch_node, child_index)) in path_to_leaf.trie_path.iter().enumerate() {
+            let builder =
+                PortalBranchNodeBuilder::new(branch_node, &path_to_leaf.trie_path[..depth])
+                    .expect("trie path should match depth");
+            let path = TriePath::from(stem[..depth].to_vec());
+            fetcher.insert(
+                &PortalVerkleContentKey::BranchBundle(BranchNodeKey {
+                    path: path.clone(),
+                    commitment: builder.bundle_commitment().clone(),
+                }),
+                &PortalVerkleNode::BranchBundle(builder.bundle_node()?),
+            );
+            let fragment_index = branch_utils::fragment_index(*child_index);
+            fetcher.insert(
+                &PortalVerkleContentKey::BranchFragment(BranchNodeKey {
+                    path,
+                    commitment: builder.fragment_commitment(fragment_index).clone(),
+                }),
+                &PortalVerkleNode::BranchFragment(builder.fragment_node(fragment_index)),
+            );
+        }
+
+        let builder = PortalLeafNodeBuilder::new(&path_to_leaf);
+        fetcher.insert(
+            &PortalVerkleContentKey::LeafBundle(LeafNodeKey {
+                stem: *stem,
+                commitment: builder.bundle_commitment().clone(),
+            }),
+            &PortalVerkleNode::LeafBundle(builder.bundle_node()?),
+        );
+        for fragment_index in 0..PORTAL_NETWORK_NODE_WIDTH as u8 {
+            if builder.fragment_commitment(fragment_index).is_zero() {
+                continue;
+            }
+            fetcher.insert(
+                &PortalVerkleContentKey::LeafFragment(LeafNodeKey {
+                    stem: *stem,
+                    commitment: builder.fragment_commitment(fragment_index).clone(),
+                }),
+                &PortalVerkleNode::LeafFragment(builder.fragment_node(fragment_index)),
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn present_keys() -> anyhow::Result<()> {
+        let (trie, state_writes) = create_trie()?;
+        let mut fetcher = MemoryNodeFetcher::new();
+        for stem_state_write in state_writes.iter().take(5) {
+            add_nodes(&mut fetcher, &trie, &stem_state_write.stem)?;
+        }
+
+        let reader = PortalStateReader::new(fetcher, trie.root_commitment().clone());
+        for stem_state_write in state_writes.iter().take(5) {
+            for (suffix, value) in &stem_state_write.writes {
+                let key = TrieKey::from_stem_and_suffix(&stem_state_write.stem, *suffix);
+                assert_eq!(reader.get(&key)?, Some(*value));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn absent_keys() -> anyhow::Result<()> {
+        let (trie, state_writes) = create_trie()?;
+        let stem = state_writes[0].stem;
+        let mut fetcher = MemoryNodeFetcher::new();
+        add_nodes(&mut fetcher, &trie, &stem)?;
+        let reader = PortalStateReader::new(fetcher, trie.root_commitment().clone());
+
+        // Absent suffix
+        let suffix = (0..=u8::MAX)
+            .find(|suffix| !state_writes[0].writes.contains_key(suffix))
+            .unwrap();
+        let key = TrieKey::from_stem_and_suffix(&stem, suffix);
+        assert_eq!(trie.get(&key)?, None);
+        assert_eq!(reader.get(&key)?, None);
+
+        // Stem that ends at the leaf with different stem
+        let mut other_stem = stem;
+        other_stem[30] ^= 1;
+        let key = TrieKey::from_stem_and_suffix(&other_stem, 0);
+        assert_eq!(trie.get(&key)?, None);
+        assert_eq!(reader.get(&key)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_node() -> anyhow::Result<()> {
+        let (trie, state_writes) = create_trie()?;
+        let reader =
+            PortalStateReader::new(MemoryNodeFetcher::new(), trie.root_commitment().clone());
+
+        let key = TrieKey::from_stem_and_suffix(&state_writes[0].stem, 0);
+        assert!(matches!(
+            reader.get(&key),
+            Err(PortalStateReaderError::MissingNode(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn missing_branch_bundle() -> anyhow::Result<()> {
+        let (trie, state_writes) = create_trie()?;
+        let stem = state_writes[0].stem;
+
+        // Only the root bundle node and the fragment node are present, the branch bundle node at
+        // the next depth is missing, even though the leaf bundle node is present
+        let path_to_leaf = trie.traverse_to_leaf(&stem)?;
+        assert!(path_to_leaf.trie_path.len() > 1);
+        let mut fetcher = MemoryNodeFetcher::new();
+        let builder = PortalBranchNodeBuilder::new(path_to_leaf.trie_path[0].0, &[])
+            .expect("trie path should match depth");
+        let fragment_index = branch_utils::fragment_index(stem[0]);
+        fetcher.insert(
+            &PortalVerkleContentKey::BranchBundle(BranchNodeKey {
+                path: TriePath::default(),
+                commitment: trie.root_commitment().clone(),
+            }),
+            &PortalVerkleNode::BranchBundle(builder.bundle_node()?),
+        );
+        fetcher.insert(
+            &PortalVerkleContentKey::BranchFragment(BranchNodeKey {
+                path: TriePath::default(),
+                commitment: builder.fragment_commitment(fragment_index).clone(),
+            }),
+            &PortalVerkleNode::BranchFragment(builder.fragment_node(fragment_index)),
+        );
+        let leaf_builder = PortalLeafNodeBuilder::new(&path_to_leaf);
+        fetcher.insert(
+            &PortalVerkleContentKey::LeafBundle(LeafNodeKey {
+                stem,
+                commitment: leaf_builder.bundle_commitment().clone(),
+            }),
+            &PortalVerkleNode::LeafBundle(leaf_builder.bundle_node()?),
+        );
+
+        let reader = PortalStateReader::new(fetcher, trie.root_commitment().clone());
+        let key = TrieKey::from_stem_and_suffix(&stem, 0);
+        let Err(PortalStateReaderError::MissingNode(content_key)) = reader.get(&key) else {
+            panic!("Expected missing node error");
+        };
+        assert_eq!(
+            *content_key,
+            PortalVerkleContentKey::BranchBundle(BranchNodeKey {
+                path: TriePath::from(stem[..1].to_vec()),
+                commitment: path_to_leaf.trie_path[1].0.commitment().to_point(),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_node() -> anyhow::Result<()> {
+        let (trie, state_writes) = create_trie()?;
+        let stem = state_writes[0].stem;
+        let mut fetcher = MemoryNodeFetcher::new();
+        add_nodes(&mut fetcher, &trie, &stem)?;
+
+        // Replace root bundle node with the bundle node from the next level
+        let path_to_leaf = trie.traverse_to_leaf(&stem)?;
+        let builder =
+            PortalBranchNodeBuilder::new(path_to_leaf.trie_path[1].0, &path_to_leaf.trie_path[..1])
+                .expect("trie path should match depth");
+        fetcher.insert(
+            &PortalVerkleContentKey::BranchBundle(BranchNodeKey {
+                path: TriePath::default(),
+                commitment: trie.root_commitment().clone(),
+            }),
+            &PortalVerkleNode::BranchBundle(builder.bundle_node()?),
+        );
+
+        let reader = PortalStateReader::new(fetcher, trie.root_commitment().clone());
+        let key = TrieKey::from_stem_and_suffix(&stem, 0);
+        assert!(matches!(
+            reader.get(&key),
+            Err(PortalStateReaderError::InvalidNode {
+                source: ContentValidationError::Verification(
+                    NodeVerificationError::WrongCommitment { .. }
+                ),
+                ..
+            })
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use alloy_primitives::B256;
+use ssz::Decode;
+
+use crate::header::{ExecutionHeader, HeaderError};
+
+use super::{
+    content_key, ContentValidationError, PortalVerkleContentKey, PortalVerkleNodeWithProof,
+};
+
+/// The registry of trusted execution headers, by their block hash.
+///
+/// It's used to validate the Portal Network content against the state root of the block that the
+/// content was created for (the `block_hash` of the node with proof), instead of against the state
+/// root provided by the caller.
+#[derive(Debug, Default)]
+pub struct TrustedHeaders {
+    headers: HashMap<B256, ExecutionHeader>,
+}
+
+impl TrustedHeaders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the RLP encoded header of the block with trusted hash (e.g. provided by the
+    /// consensus light client).
+    ///
+    /// Returns error if header can't be decoded or if its hash doesn't match the block hash.
+    pub fn insert(
+        &mut self,
+        block_hash: B256,
+        header_rlp: &[u8],
+    ) -> Result<&ExecutionHeader, HeaderError> {
+        let header = ExecutionHeader::decode_and_verify(header_rlp, &block_hash)?;
+        Ok(self.headers.entry(block_hash).or_insert(header))
+    }
+
+    /// Removes the header (e.g. once the block is no longer of interest).
+    pub fn remove(&mut self, block_hash: &B256) -> Option<ExecutionHeader> {
+        self.headers.remove(block_hash)
+    }
+
+    pub fn get(&self, block_hash: &B256) -> Option<&ExecutionHeader> {
+        self.headers.get(block_hash)
+    }
+
+    pub fn state_root(&self, block_hash: &B256) -> Option<B256> {
+        self.get(block_hash).map(|header| header.state_root)
+    }
+
+    /// Validates the content (node with proof), as received when content is offered.
+    ///
+    /// Same as [validate](super::validate), except that the state root is taken from the trusted
+    /// header of the content's block.
+    pub fn validate(
+        &self,
+        content_key: &PortalVerkleContentKey,
+        content_bytes: &[u8],
+    ) -> Result<PortalVerkleNodeWithProof, ContentValidationError> {
+        let content = PortalVerkleNodeWithProof::from_ssz_bytes(content_bytes)?;
+        self.verify(content_key, &content)?;
+        Ok(content)
+    }
+
+    /// Verifies the node with proof against the content key and the state root of the content's
+    /// block.
+    pub fn verify(
+        &self,
+        content_key: &PortalVerkleContentKey,
+        content: &PortalVerkleNodeWithProof,
+    ) -> Result<(), ContentValidationError> {
+        let block_hash = content.block_hash();
+        let state_root = self
+            .state_root(&block_hash)
+            .ok_or(ContentValidationError::UnknownBlockHash(block_hash))?;
+        content_key::verify_content(content_key, content, &state_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, fs::File, io::BufReader};
+
+    use alloy_primitives::hex;
+    use ssz::Encode;
+
+    use crate::{
+        portal::{LeafNodeKey, NodeVerificationError},
+        verkle::{
+            genesis_config::GenesisConfig, nodes::portal_leaf_node_builder::PortalLeafNodeBuilder,
+            VerkleTrie,
+        },
+    };
+
+    use super::*;
+
+    fn read_genesis_header_rlp() -> anyhow::Result<Vec<u8>> {
+        let rlp = fs::read_to_string("../testdata/devnet6_genesis_header.hex")?;
+        Ok(hex::decode(rlp.trim())?)
+    }
+
+    /// Returns the content key and the leaf bundle node (created for the given block hash) of
+    /// one of the genesis stems.
+    fn create_leaf_bundle(
+        block_hash: B256,
+    ) -> anyhow::Result<(PortalVerkleContentKey, PortalVerkleNodeWithProof)> {
+        let reader = BufReader::new(File::open("../testdata/genesis.json")?);
+        let genesis_config: GenesisConfig = serde_json::from_reader(reader)?;
+        let state_writes = genesis_config.into_state_writes();
+        let mut trie = VerkleTrie::new();
+        trie.update(&state_writes)?;
+
+        let stem = state_writes[0].stem;
+        let path_to_leaf = trie.traverse_to_leaf(&stem)?;
+        let builder = PortalLeafNodeBuilder::new(&path_to_leaf);
+        let content_key = PortalVerkleContentKey::LeafBundle(LeafNodeKey {
+            stem,
+            commitment: builder.bundle_commitment().clone(),
+        });
+        let content =
+            PortalVerkleNodeWithProof::LeafBundle(builder.bundle_node_with_proof(block_hash)?);
+        Ok((content_key, content))
+    }
+
+    #[test]
+    fn insert() -> anyhow::Result<()> {
+        let rlp = read_genesis_header_rlp()?;
+        let mut trusted_headers = TrustedHeaders::new();
+
+        assert!(matches!(
+            trusted_headers.insert(B256::ZERO, &rlp),
+            Err(HeaderError::WrongBlockHash { .. })
+        ));
+        assert!(trusted_headers.get(&B256::ZERO).is_none());
+
+        trusted_headers.insert(GenesisConfig::DEVNET6_BLOCK_HASH, &rlp)?;
+        assert_eq!(
+            trusted_headers.state_root(&GenesisConfig::DEVNET6_BLOCK_HASH),
+            Some(GenesisConfig::DEVNET6_STATE_ROOT)
+        );
+
+        assert!(trusted_headers
+            .remove(&GenesisConfig::DEVNET6_BLOCK_HASH)
+            .is_some());
+        assert!(trusted_headers
+            .state_root(&GenesisConfig::DEVNET6_BLOCK_HASH)
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn validate() -> anyhow::Result<()> {
+        let mut trusted_headers = TrustedHeaders::new();
+        trusted_headers.insert(
+            GenesisConfig::DEVNET6_BLOCK_HASH,
+            &read_genesis_header_rlp()?,
+        )?;
+
+        let (content_key, content) = create_leaf_bundle(GenesisConfig::DEVNET6_BLOCK_HASH)?;
+        assert_eq!(
+            trusted_headers
+                .validate(&content_key, &content.as_ssz_bytes())?
+                .as_ssz_bytes(),
+            content.as_ssz_bytes()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validate_unknown_block_hash() -> anyhow::Result<()> {
+        let mut trusted_headers = TrustedHeaders::new();
+        trusted_headers.insert(
+            GenesisConfig::DEVNET6_BLOCK_HASH,
+            &read_genesis_header_rlp()?,
+        )?;
+
+        let block_hash = B256::repeat_byte(1);
+        let (content_key, content) = create_leaf_bundle(block_hash)?;
+        assert!(matches!(
+            trusted_headers.validate(&content_key, &content.as_ssz_bytes()),
+            Err(ContentValidationError::UnknownBlockHash(hash)) if hash == block_hash
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn validate_wrong_state_root() -> anyhow::Result<()> {
+        // The header of the block with different state root
+        let mut header = ExecutionHeader::decode(&read_genesis_header_rlp()?)?;
+        header.state_root = B256::repeat_byte(1);
+        let block_hash = header.hash();
+        let mut trusted_headers = TrustedHeaders::new();
+        trusted_headers.insert(block_hash, &alloy_rlp::encode(&header))?;
+
+        let (content_key, content) = create_leaf_bundle(block_hash)?;
+        assert!(matches!(
+            trusted_headers.validate(&content_key, &content.as_ssz_bytes()),
+            Err(ContentValidationError::Verification(
+                NodeVerificationError::WrongRoot { .. }
+            ))
+        ));
+        Ok(())
+    }
+}
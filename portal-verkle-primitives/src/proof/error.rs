@@ -0,0 +1,20 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ProofError {
+    #[error("Multiquery doesn't have any query")]
+    EmptyMultiQuery,
+
+    #[error("Proof has {cl} left and {cr} right commitments, but expected {expected} of each")]
+    ProofLengthMismatch {
+        cl: usize,
+        cr: usize,
+        expected: usize,
+    },
+
+    #[error("Challenge (evaluation point) is in the domain")]
+    ChallengeInDomain,
+
+    #[error("Verification equation doesn't hold")]
+    VerificationFailed,
+}
@@ -1,57 +1,62 @@
-use std::iter;
+use std::{array, iter};
 
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, EdwardsProjective};
 use itertools::{chain, zip_eq, Itertools};
 use serde::{Deserialize, Serialize};
-use ssz_derive::{Decode, Encode};
-use ssz_types::{typenum, FixedVector};
+use ssz::{Decode, DecodeError, Encode};
 
 use crate::{
-    constants::{VERKLE_NODE_WIDTH, VERKLE_NODE_WIDTH_BITS},
-    ec::CRS,
-    proof::precomputed_weights::PrecomputedWeights,
-    utils::array_long_const,
-    BatchInversion, DotProduct, Point, ScalarField,
+    constants::VERKLE_NODE_WIDTH, ec::CRS, proof::precomputed_weights::PrecomputedWeights,
+    utils::DomainSize, BatchInversion, DotProduct, Point, ScalarField,
 };
 
-use super::{lagrange_basis::LagrangeBasis, transcript::Transcript};
+use super::{
+    lagrange_basis::LagrangeBasis,
+    transcript::{Transcript, TranscriptProtocol},
+    ProofError,
+};
 
-/// The inner product argument proof.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
+/// The inner product argument proof, for polynomials over the domain of size `N`.
+///
+/// The `cl` and `cr` should have `log2(N)` elements each (one per reduction round).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
-pub struct IpaProof {
-    pub cl: FixedVector<Point, typenum::U8>,
-    pub cr: FixedVector<Point, typenum::U8>,
+pub struct IpaProof<const N: usize = VERKLE_NODE_WIDTH> {
+    pub cl: Vec<Point>,
+    pub cr: Vec<Point>,
     #[serde(alias = "finalEvaluation")]
     pub final_evaluation: ScalarField,
 }
 
-// TODO: Add this to Derive once ssz_types updates.
-impl Eq for IpaProof {}
-
-impl IpaProof {
+impl<const N: usize> IpaProof<N> {
     /// Opens given polynomial with commitment `C` at `x`.
     ///
-    /// The `x` is outside of the domain.
+    /// The `x` has to be outside of the domain.
     pub fn open_polynomial(
         c: Option<Point>,
-        polynomial: LagrangeBasis,
+        polynomial: LagrangeBasis<N>,
         x: ScalarField,
-        transcript: &mut Transcript,
-    ) -> Self {
+        transcript: &mut impl TranscriptProtocol,
+    ) -> Result<Self, ProofError> {
+        check_outside_of_domain::<N>(&x)?;
+
         // 0. Prepare variables
         let c = c.unwrap_or_else(|| polynomial.commit());
         let mut a_orig = polynomial.evaluations().clone();
-        let mut b_orig = PrecomputedWeights::evaluate_lagrange_polynomials(&x);
-        let mut g_orig = CRS::bases().clone();
+        let mut b_orig = PrecomputedWeights::<N>::evaluate_lagrange_polynomials(&x);
 
         let y = zip_eq(&a_orig, &b_orig).map(|(a, b)| a * b).sum();
 
         let mut a = a_orig.as_mut_slice();
         let mut b = b_orig.as_mut_slice();
-        let mut g = g_orig.as_mut_slice();
 
-        let mut cls = Vec::with_capacity(VERKLE_NODE_WIDTH_BITS);
-        let mut crs = Vec::with_capacity(VERKLE_NODE_WIDTH_BITS);
+        // The reduced G, in affine representation (which makes both MSMs and the reduction faster).
+        // In the first round, G is not reduced yet, so precomputed CRS tables are used instead.
+        let mut g: Vec<EdwardsAffine> = CRS::<N>::affine_bases().to_vec();
+
+        let mut cls = Vec::with_capacity(DomainSize::<N>::BITS);
+        let mut crs = Vec::with_capacity(DomainSize::<N>::BITS);
 
         // 1. Compute w and Q
         transcript.domain_sep("ipa");
@@ -64,14 +69,14 @@ impl IpaProof {
         let q = CRS::generator().mul(&w);
 
         // 2. Run reduction
-        let mut n = VERKLE_NODE_WIDTH;
+        let mut n = N;
         while n > 1 {
             let n_half = n / 2;
 
             // 2.1 Split a, b, G
             let (a_l, a_r) = a.split_at_mut(n_half);
             let (b_l, b_r) = b.split_at_mut(n_half);
-            let (g_l, g_r) = g.split_at_mut(n_half);
+            let (g_l, g_r) = g.split_at(n_half);
 
             // 2.2 Compute z_l and z_r
 
@@ -81,20 +86,31 @@ impl IpaProof {
             let z_r = ScalarField::dot_product(a_l.iter(), b_r.iter());
 
             // 2.3 Compute, commit to, and save C_l and C_r
-
             // C_l = sum(a_r_i * G_l_i) + z_l * Q
-            let c_l = Point::multi_scalar_mul(
-                g_l.iter().chain(iter::once(&q)),
-                a_r.iter().chain(iter::once(&z_l)),
-            );
+            // C_r = sum(a_l_i * G_r_i) + z_r * Q
+            let (c_l, c_r) = if n == N {
+                // G is not reduced yet, so precomputed CRS tables can be used
+                let c_l_scalars = array::from_fn(|j| match j {
+                    j if j < n_half => a_r[j].clone(),
+                    _ => ScalarField::zero(),
+                });
+                let c_r_scalars = array::from_fn(|j| match j {
+                    j if j >= n_half => a_l[j - n_half].clone(),
+                    _ => ScalarField::zero(),
+                });
+                (
+                    CRS::<N>::commit(&c_l_scalars) + q.mul(&z_l),
+                    CRS::<N>::commit(&c_r_scalars) + q.mul(&z_r),
+                )
+            } else {
+                let msm = |bases: &[EdwardsAffine], scalars: &[ScalarField]| {
+                    let scalars = scalars.iter().map(ScalarField::inner).collect_vec();
+                    Point::from_projective(EdwardsProjective::msm_unchecked(bases, &scalars))
+                };
+                (msm(g_l, a_r) + q.mul(&z_l), msm(g_r, a_l) + q.mul(&z_r))
+            };
             transcript.append_point("L", &c_l);
             cls.push(c_l);
-
-            // C_r = sum(a_l_i * G_r_i) + z_r * Q
-            let c_r = Point::multi_scalar_mul(
-                g_r.iter().chain(iter::once(&q)),
-                a_l.iter().chain(iter::once(&z_r)),
-            );
             transcript.append_point("R", &c_r);
             crs.push(c_r);
 
@@ -106,19 +122,76 @@ impl IpaProof {
             for i in 0..n_half {
                 a_l[i] += &a_r[i] * &x;
                 b_l[i] += &b_r[i] * &x_inv;
-                g_l[i] += g_r[i].mul(&x_inv);
             }
+            // G_l_i += G_r_i * x_inv, normalized with a single (batch) inversion
+            let g_reduced = zip_eq(g_l, g_r)
+                .map(|(g_l_i, g_r_i)| *g_r_i * x_inv.inner() + g_l_i)
+                .collect_vec();
+            g = EdwardsProjective::normalize_batch(&g_reduced);
             a = a_l;
             b = b_l;
-            g = g_l;
 
             // 2.6 Update n
             n = n_half;
         }
 
+        Ok(Self {
+            cl: cls,
+            cr: crs,
+            final_evaluation: a[0].clone(),
+        })
+    }
+
+    /// The straightforward implementation of [open_polynomial](Self::open_polynomial), that
+    /// doesn't use precomputed CRS tables and keeps `G` in projective representation.
+    ///
+    /// Used to check that both create the same proof and to measure the speedup.
+    #[cfg(any(test, feature = "bench"))]
+    #[doc(hidden)]
+    pub fn open_polynomial_reference(
+        polynomial: LagrangeBasis<N>,
+        x: ScalarField,
+        transcript: &mut impl TranscriptProtocol,
+    ) -> Self {
+        let c = polynomial.commit();
+        let mut a = polynomial.evaluations().to_vec();
+        let mut b = PrecomputedWeights::<N>::evaluate_lagrange_polynomials(&x).to_vec();
+        let mut g = CRS::<N>::bases().to_vec();
+        let y = ScalarField::dot_product(&a, &b);
+
+        transcript.domain_sep("ipa");
+        transcript.append_point("C", &c);
+        transcript.append_scalar("input point", &x);
+        transcript.append_scalar("output point", &y);
+        let q = CRS::generator().mul(&transcript.challenge_scalar("w"));
+
+        let mut cl = vec![];
+        let mut cr = vec![];
+        while a.len() > 1 {
+            let n_half = a.len() / 2;
+            let (a_l, a_r) = a.split_at(n_half);
+            let (b_l, b_r) = b.split_at(n_half);
+            let (g_l, g_r) = g.split_at(n_half);
+
+            let z_l = ScalarField::dot_product(a_r, b_l);
+            let z_r = ScalarField::dot_product(a_l, b_r);
+            let c_l = Point::multi_scalar_mul(chain!(g_l, [&q]), chain!(a_r, [&z_l]));
+            let c_r = Point::multi_scalar_mul(chain!(g_r, [&q]), chain!(a_l, [&z_r]));
+            transcript.append_point("L", &c_l);
+            transcript.append_point("R", &c_r);
+            cl.push(c_l);
+            cr.push(c_r);
+
+            let x = transcript.challenge_scalar("x");
+            let x_inv = x.inverse().expect("x shouldn't be zero");
+            a = zip_eq(a_l, a_r).map(|(l, r)| l + r * &x).collect();
+            b = zip_eq(b_l, b_r).map(|(l, r)| l + r * &x_inv).collect();
+            g = zip_eq(g_l, g_r).map(|(l, r)| r.mul(&x_inv) + l).collect();
+        }
+
         Self {
-            cl: FixedVector::new(cls).expect("The cl should have expected length"),
-            cr: FixedVector::new(crs).expect("The cr should have expected length"),
+            cl,
+            cr,
             final_evaluation: a[0].clone(),
         }
     }
@@ -129,12 +202,32 @@ impl IpaProof {
         c: Point,
         x: ScalarField,
         y: ScalarField,
-        transcript: &mut Transcript,
-    ) -> bool {
+        transcript: &mut impl TranscriptProtocol,
+    ) -> Result<(), ProofError> {
+        self.verification_equation(c, x, y, transcript)?.check()
+    }
+
+    /// Returns the equation that holds if polynomial with commitment `C` evaluates to `y` at `x`.
+    pub(crate) fn verification_equation(
+        &self,
+        c: Point,
+        x: ScalarField,
+        y: ScalarField,
+        transcript: &mut impl TranscriptProtocol,
+    ) -> Result<IpaVerificationEquation<N>, ProofError> {
+        let rounds = DomainSize::<N>::BITS;
+        if self.cl.len() != rounds || self.cr.len() != rounds {
+            return Err(ProofError::ProofLengthMismatch {
+                cl: self.cl.len(),
+                cr: self.cr.len(),
+                expected: rounds,
+            });
+        }
+        check_outside_of_domain::<N>(&x)?;
+
         // 0. Prepare variables
         let a_prime = &self.final_evaluation;
-        let q = CRS::generator();
-        let b = PrecomputedWeights::evaluate_lagrange_polynomials(&x);
+        let b = PrecomputedWeights::<N>::evaluate_lagrange_polynomials(&x);
 
         // 1. Compute w
         // NOTE: we should multiply Q with it, but we will do it later
@@ -143,6 +236,7 @@ impl IpaProof {
         transcript.append_scalar("input point", &x);
         transcript.append_scalar("output point", &y);
         let w = transcript.challenge_scalar("w");
+        let (input_point, output_point) = (x, y.clone());
 
         // 2. Compute x_i and 1/x_i
         let x = zip_eq(&self.cl, &self.cr)
@@ -158,11 +252,11 @@ impl IpaProof {
         // They are calculated by aggregating 1/x_i (x_agg_i) in a smart way.
         // We also multiply them with -a' to avoid that step in the future, as it is needed for
         // multiplication with both G and b.
-        let mut g_coeff: [ScalarField; VERKLE_NODE_WIDTH] = array_long_const(-a_prime);
+        let mut g_coeff: [ScalarField; N] = array::from_fn(|_| -a_prime);
         for (i, g_coeff) in g_coeff.iter_mut().enumerate() {
-            for bit_index in 0..VERKLE_NODE_WIDTH_BITS {
+            for bit_index in 0..rounds {
                 if i & (1 << bit_index) != 0 {
-                    let x_index = VERKLE_NODE_WIDTH_BITS - 1 - bit_index;
+                    let x_index = rounds - 1 - bit_index;
                     *g_coeff *= &x_inv[x_index];
                 }
             }
@@ -172,17 +266,175 @@ impl IpaProof {
         // q_coeff = w * (y + sum(b_i * (-a' * x_agg_i))) = w * (y + sum(b_i * g_coeff_i)))
         let q_coeff = w * (y + ScalarField::dot_product(b, &g_coeff));
 
-        // 5. Verify that equation holds:
+        // 5. The equation that should hold:
         // C + Q * w * y + sum(Cl_i * x_i) + sum(Cr_i / x_i) = G' * a' + Q * w * (a'b')
         // Where G' = sum(G_i * x_agg_i) and b' = sum(b_i * x_agg_i)
         // Simplified, this is equal to:
         // C + Q * q_coeff + sum(Cl_i * x_i) + sum(Cr_i / x_i) + sum(G_i * g_coeff_i) = 0
-        let c_coeff = ScalarField::one();
+        Ok(IpaVerificationEquation {
+            points: chain!(
+                iter::once(c),
+                self.cl.iter().cloned(),
+                self.cr.iter().cloned()
+            )
+            .collect(),
+            scalars: chain!(iter::once(ScalarField::one()), x, x_inv).collect(),
+            q_coeff,
+            g_coeff,
+            input_point,
+            output_point,
+            final_evaluation: a_prime.clone(),
+        })
+    }
+}
+
+/// The IPA verification equation, in the form:
+///
+/// `sum(points_i * scalars_i) + Q * q_coeff + sum(G_i * g_coeff_i) = 0`
+///
+/// Where `Q` is the [CRS::generator] and `G_i` are the [CRS::bases].
+pub(crate) struct IpaVerificationEquation<const N: usize> {
+    points: Vec<Point>,
+    scalars: Vec<ScalarField>,
+    q_coeff: ScalarField,
+    g_coeff: [ScalarField; N],
+    // The inputs that, together with the `points`, determine the equation (used for batching)
+    input_point: ScalarField,
+    output_point: ScalarField,
+    final_evaluation: ScalarField,
+}
+
+impl<const N: usize> IpaVerificationEquation<N> {
+    pub fn check(&self) -> Result<(), ProofError> {
         let result = Point::multi_scalar_mul(
-            chain!(iter::once(&c), iter::once(q), &self.cl, &self.cr),
-            chain!(iter::once(&c_coeff), iter::once(&q_coeff), &x, &x_inv),
+            chain!(&self.points, iter::once(CRS::generator())),
+            chain!(&self.scalars, iter::once(&self.q_coeff)),
+        ) + CRS::commit(&self.g_coeff);
+        check_is_zero(&result)
+    }
+
+    /// Checks that all equations hold, by checking their random linear combination.
+    ///
+    /// The random coefficients are derived from all equations using Fiat-Shamir heuristic, so
+    /// the check costs one multi-scalar multiplication (and one commitment to the CRS bases),
+    /// regardless of the number of equations.
+    pub fn check_all(equations: &[Self]) -> Result<(), ProofError> {
+        match equations {
+            [] => return Ok(()),
+            [equation] => return equation.check(),
+            _ => {}
+        }
+
+        // 1. Compute r
+        // r = H(C_0, x_0, y_0, proof_0, ..., C_n, x_n, y_n, proof_n)
+        // All coefficients of the equation are derived from these inputs.
+        let mut transcript = Transcript::new("ipa_batch");
+        for equation in equations {
+            // C, followed by L_i and R_i from the proof
+            for point in &equation.points {
+                transcript.append_point("P", point);
+            }
+            transcript.append_scalar("input point", &equation.input_point);
+            transcript.append_scalar("output point", &equation.output_point);
+            transcript.append_scalar("final evaluation", &equation.final_evaluation);
+        }
+        let r = transcript.challenge_scalar("r");
+
+        // 2. Compute the combined equation
+        // sum(r^i * equation_i) = 0
+        let powers_of_r = ScalarField::powers_of(&r, equations.len());
+        let mut scalars = Vec::with_capacity(equations.iter().map(|eq| eq.scalars.len()).sum());
+        let mut q_coeff = ScalarField::zero();
+        let mut g_coeff: [ScalarField; N] = array::from_fn(|_| ScalarField::zero());
+        for (power_of_r, equation) in zip_eq(&powers_of_r, equations) {
+            scalars.extend(equation.scalars.iter().map(|scalar| scalar * power_of_r));
+            q_coeff += &equation.q_coeff * power_of_r;
+            for (g_coeff, equation_g_coeff) in zip_eq(&mut g_coeff, &equation.g_coeff) {
+                *g_coeff += equation_g_coeff * power_of_r;
+            }
+        }
+
+        let result = Point::multi_scalar_mul(
+            chain!(
+                equations.iter().flat_map(|equation| &equation.points),
+                iter::once(CRS::generator())
+            ),
+            chain!(&scalars, iter::once(&q_coeff)),
         ) + CRS::commit(&g_coeff);
-        result.is_zero()
+        check_is_zero(&result)
+    }
+}
+
+fn check_is_zero(result: &Point) -> Result<(), ProofError> {
+    if result.is_zero() {
+        Ok(())
+    } else {
+        Err(ProofError::VerificationFailed)
+    }
+}
+
+/// Checks that the evaluation point is outside of the domain of size `N`.
+pub(super) fn check_outside_of_domain<const N: usize>(x: &ScalarField) -> Result<(), ProofError> {
+    if x >= &ScalarField::from(N) {
+        Ok(())
+    } else {
+        Err(ProofError::ChallengeInDomain)
+    }
+}
+
+impl<const N: usize> Encode for IpaProof<N> {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        2 * DomainSize::<N>::BITS * <Point as Encode>::ssz_fixed_len()
+            + <ScalarField as Encode>::ssz_fixed_len()
+    }
+
+    fn ssz_append(&self, buf: &mut Vec<u8>) {
+        for point in chain!(&self.cl, &self.cr) {
+            point.ssz_append(buf);
+        }
+        self.final_evaluation.ssz_append(buf);
+    }
+
+    fn ssz_bytes_len(&self) -> usize {
+        (self.cl.len() + self.cr.len()) * <Point as Encode>::ssz_fixed_len()
+            + <ScalarField as Encode>::ssz_fixed_len()
+    }
+}
+
+impl<const N: usize> Decode for IpaProof<N> {
+    fn is_ssz_fixed_len() -> bool {
+        true
+    }
+
+    fn ssz_fixed_len() -> usize {
+        <Self as Encode>::ssz_fixed_len()
+    }
+
+    fn from_ssz_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let expected = <Self as Decode>::ssz_fixed_len();
+        if bytes.len() != expected {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected,
+            });
+        }
+
+        let (points, final_evaluation) =
+            bytes.split_at(expected - <ScalarField as Decode>::ssz_fixed_len());
+        let mut points = points
+            .chunks_exact(<Point as Decode>::ssz_fixed_len())
+            .map(Point::from_ssz_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+        let cr = points.split_off(DomainSize::<N>::BITS);
+        Ok(Self {
+            cl: points,
+            cr,
+            final_evaluation: ScalarField::from_ssz_bytes(final_evaluation)?,
+        })
     }
 }
 
@@ -192,17 +444,37 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn same_as_reference() {
+        let x = ScalarField::from(1234u64);
+        let poly = LagrangeBasis::new(array_long(|i| ScalarField::from(i as u64 * 7919 + 1)));
+        let proof =
+            IpaProof::open_polynomial(None, poly.clone(), x.clone(), &mut Transcript::new("test"))
+                .unwrap();
+        let reference =
+            IpaProof::open_polynomial_reference(poly, x.clone(), &mut Transcript::new("test"));
+        assert_eq!(proof.as_ssz_bytes(), reference.as_ssz_bytes());
+
+        let poly = LagrangeBasis::<16>::new(array::from_fn(|i| -ScalarField::from(i)));
+        let proof =
+            IpaProof::open_polynomial(None, poly.clone(), x.clone(), &mut Transcript::new("test"))
+                .unwrap();
+        let reference = IpaProof::open_polynomial_reference(poly, x, &mut Transcript::new("test"));
+        assert_eq!(proof.as_ssz_bytes(), reference.as_ssz_bytes());
+    }
+
     #[test]
     fn const_polynomial() {
         let x = ScalarField::from(1234u64);
         let y = ScalarField::from(42u64);
-        let poly = LagrangeBasis::new_const(&y);
+        let poly = LagrangeBasis::<VERKLE_NODE_WIDTH>::new_const(&y);
         let c = poly.commit();
         let mut transcript = Transcript::new("test");
-        let proof = IpaProof::open_polynomial(Some(c.clone()), poly, x.clone(), &mut transcript);
+        let proof =
+            IpaProof::open_polynomial(Some(c.clone()), poly, x.clone(), &mut transcript).unwrap();
 
         let mut transcript = Transcript::new("test");
-        assert!(proof.verify_polynomial(c, x, y, &mut transcript))
+        assert!(proof.verify_polynomial(c, x, y, &mut transcript).is_ok())
     }
 
     #[test]
@@ -218,9 +490,81 @@ mod tests {
         let poly = LagrangeBasis::new(array_long(|i| eval(&ScalarField::from(i))));
         let c = poly.commit();
         let mut transcript = Transcript::new("test");
-        let proof = IpaProof::open_polynomial(Some(c.clone()), poly, x.clone(), &mut transcript);
+        let proof =
+            IpaProof::open_polynomial(Some(c.clone()), poly, x.clone(), &mut transcript).unwrap();
+
+        let mut transcript = Transcript::new("test");
+        assert!(proof.verify_polynomial(c, x, y, &mut transcript).is_ok())
+    }
+
+    #[test]
+    fn wrong_evaluation() {
+        let x = ScalarField::from(1234u64);
+        let poly = LagrangeBasis::<VERKLE_NODE_WIDTH>::new_const(&ScalarField::from(42u64));
+        let c = poly.commit();
+        let mut transcript = Transcript::new("test");
+        let proof =
+            IpaProof::open_polynomial(Some(c.clone()), poly, x.clone(), &mut transcript).unwrap();
+
+        let mut transcript = Transcript::new("test");
+        assert!(matches!(
+            proof.verify_polynomial(c, x, ScalarField::from(43u64), &mut transcript),
+            Err(ProofError::VerificationFailed)
+        ));
+    }
+
+    #[test]
+    fn smaller_domain() {
+        // poly = (x+1)(x-10)
+        let eval = |x: &ScalarField| (x + ScalarField::from(1u64)) * (x - ScalarField::from(10u64));
+        let x = ScalarField::from(1234u64);
+        let y = eval(&x);
+        let poly = LagrangeBasis::<16>::new(array::from_fn(|i| eval(&ScalarField::from(i))));
+        let c = poly.commit();
+        let mut transcript = Transcript::new("test");
+        let proof =
+            IpaProof::open_polynomial(Some(c.clone()), poly, x.clone(), &mut transcript).unwrap();
+        assert_eq!(proof.cl.len(), 4);
+        assert_eq!(proof.cr.len(), 4);
+        assert_eq!(
+            IpaProof::<16>::from_ssz_bytes(&proof.as_ssz_bytes()).unwrap(),
+            proof
+        );
+
+        let mut transcript = Transcript::new("test");
+        assert!(proof
+            .verify_polynomial(c.clone(), x.clone(), y.clone(), &mut transcript)
+            .is_ok());
 
+        // Proof of the Verkle trie domain size doesn't fit
         let mut transcript = Transcript::new("test");
-        assert!(proof.verify_polynomial(c, x, y, &mut transcript))
+        assert!(matches!(
+            IpaProof::<VERKLE_NODE_WIDTH>::from_ssz_bytes(&proof.as_ssz_bytes()),
+            Err(DecodeError::InvalidByteLength { .. })
+        ));
+        let proof = IpaProof::<VERKLE_NODE_WIDTH> {
+            cl: proof.cl,
+            cr: proof.cr,
+            final_evaluation: proof.final_evaluation,
+        };
+        assert!(matches!(
+            proof.verify_polynomial(c, x, y, &mut transcript),
+            Err(ProofError::ProofLengthMismatch {
+                cl: 4,
+                cr: 4,
+                expected: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn evaluation_point_in_domain() {
+        let x = ScalarField::from(VERKLE_NODE_WIDTH - 1);
+        let poly = LagrangeBasis::<VERKLE_NODE_WIDTH>::new_const(&ScalarField::from(42u64));
+        let c = poly.commit();
+        assert!(matches!(
+            IpaProof::open_polynomial(Some(c), poly, x, &mut Transcript::new("test")),
+            Err(ProofError::ChallengeInDomain)
+        ));
     }
 }
@@ -1,15 +1,14 @@
-use std::{iter::Sum, ops};
+use std::{
+    array,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign},
+};
 
 use itertools::Itertools;
-use overload::overload;
 
-use crate::{
-    constants::VERKLE_NODE_WIDTH,
-    utils::{array_long, array_long_const},
-    DotProduct, Point, ScalarField, CRS,
-};
+use crate::{constants::VERKLE_NODE_WIDTH, utils::DomainSize, DotProduct, Point, ScalarField, CRS};
 
-use super::precomputed_weights::PrecomputedWeights;
+use super::{monomial_basis::MonomialBasis, precomputed_weights::PrecomputedWeights};
 
 /// The polynomial expressed using Lagrange's form.
 ///
@@ -19,7 +18,8 @@ use super::precomputed_weights::PrecomputedWeights;
 /// ```
 ///
 /// Another way to represent the polynomial is using Langrange basis, in which we store the value
-/// that polynomial has on a given domain (in our case domain is `[0, 255]`). More precisely:
+/// that polynomial has on a given domain (`[0, N-1]`, which is `[0, 255]` for the Verkle trie).
+/// More precisely:
 ///
 /// ```text
 /// P(x) = y_0 * L_0(x) + y_1 * L_1(x) + ... + y_n * L_n(x)
@@ -34,17 +34,18 @@ use super::precomputed_weights::PrecomputedWeights;
 ///         j≠i  i-j
 /// ```
 #[derive(Clone, Debug)]
-pub struct LagrangeBasis {
-    y: [ScalarField; VERKLE_NODE_WIDTH],
+pub struct LagrangeBasis<const N: usize = VERKLE_NODE_WIDTH> {
+    y: [ScalarField; N],
 }
 
-impl LagrangeBasis {
-    pub fn new(values: [ScalarField; VERKLE_NODE_WIDTH]) -> Self {
+impl<const N: usize> LagrangeBasis<N> {
+    pub fn new(values: [ScalarField; N]) -> Self {
+        let () = DomainSize::<N>::CHECK;
         Self { y: values }
     }
 
     pub fn new_const(value: &ScalarField) -> Self {
-        Self::new(array_long_const(value.clone()))
+        Self::new(array::from_fn(|_| value.clone()))
     }
 
     pub fn zero() -> Self {
@@ -56,7 +57,7 @@ impl LagrangeBasis {
     }
 
     /// Returns evaluations on the domain
-    pub fn evaluations(&self) -> &[ScalarField; VERKLE_NODE_WIDTH] {
+    pub fn evaluations(&self) -> &[ScalarField; N] {
         &self.y
     }
 
@@ -68,10 +69,79 @@ impl LagrangeBasis {
     /// Calculates `P(z)` for `z` outside domain
     pub fn evaluate_outside_domain(&self, z: &ScalarField) -> ScalarField {
         // Lagrange polinomials: L_i(z)
-        let l = PrecomputedWeights::evaluate_lagrange_polynomials(z);
+        let l = PrecomputedWeights::<N>::evaluate_lagrange_polynomials(z);
         ScalarField::dot_product(l, &self.y)
     }
 
+    /// Calculates `P(z)` for any `z`
+    pub fn evaluate(&self, z: &ScalarField) -> ScalarField {
+        if z < &ScalarField::from(N) {
+            if let Some(i) = (0..N).find(|i| &ScalarField::from(*i) == z) {
+                return self.y[i].clone();
+            }
+        }
+        self.evaluate_outside_domain(z)
+    }
+
+    /// Returns the polynomial that goes through all given points (`(x_i, y_i)`).
+    ///
+    /// Returns `None` if the same `x_i` is present more than once, or if interpolated polynomial
+    /// has degree `N` or higher (which is possible only if there are more than `N` points).
+    pub fn interpolate(points: &[(ScalarField, ScalarField)]) -> Option<Self> {
+        Self::from_monomial(&MonomialBasis::interpolate(points)?)
+    }
+
+    /// Converts the polynomial from coefficient (monomial) basis, by evaluating it on domain.
+    ///
+    /// Returns `None` if polynomial has degree `N` or higher, as it can't be represented on the
+    /// domain.
+    pub fn from_monomial(poly: &MonomialBasis) -> Option<Self> {
+        if poly.degree().is_some_and(|degree| degree >= N) {
+            return None;
+        }
+        Some(Self::new(array::from_fn(|i| {
+            poly.evaluate(&ScalarField::from(i))
+        })))
+    }
+
+    /// Converts the polynomial into coefficient (monomial) basis.
+    ///
+    /// ```text
+    ///                  A(x)
+    /// P(x) = ∑ y_i * -------------
+    ///        i        A'(i) * (x-i)
+    /// ```
+    pub fn to_monomial(&self) -> MonomialBasis {
+        let a = MonomialBasis::vanishing(&array::from_fn::<_, N, _>(ScalarField::from));
+        self.y
+            .iter()
+            .enumerate()
+            .filter(|(_, y)| !y.is_zero())
+            .map(|(i, y)| {
+                a.divide_by_root(&ScalarField::from(i))
+                    * (y * PrecomputedWeights::<N>::a_prime_inv(i as u8))
+            })
+            .fold(MonomialBasis::zero(), |sum, poly| sum + poly)
+    }
+
+    /// Returns the degree of the polynomial, or `None` for zero polynomial.
+    ///
+    /// This requires conversion to coefficient (monomial) basis.
+    pub fn degree(&self) -> Option<usize> {
+        self.to_monomial().degree()
+    }
+
+    /// Multiplies polynomials, by multiplying their evaluations on domain.
+    ///
+    /// Returns `None` if the product has degree `N` or higher, as it can't be represented on the
+    /// domain.
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        match (self.degree(), other.degree()) {
+            (Some(lhs), Some(rhs)) if lhs + rhs >= N => None,
+            _ => Some(Self::new(array::from_fn(|i| &self.y[i] * &other.y[i]))),
+        }
+    }
+
     /// Divides the polynomial `P(x)-P(k)` with `x-k`, where `k` is in domain.
     ///
     /// Let's call new polynomial `Q(x)`. We evaluate it on domain manually:
@@ -85,71 +155,265 @@ impl LagrangeBasis {
     /// Q(k) = ∑ -Q(j) * A'(k) / A'(j)
     ///       j≠k
     /// ```
+    ///
+    /// The `k` should be smaller than `N` (this is checked only in debug builds), otherwise this
+    /// panics.
     pub fn divide_on_domain(&self, k: u8) -> Self {
+        debug_assert!(
+            (k as usize) < N,
+            "Index {k} is outside of the domain of size {N}"
+        );
         let k_usize = k as usize;
-        let mut q = array_long_const(ScalarField::zero());
-        for i in 0..VERKLE_NODE_WIDTH {
+        let mut q = array::from_fn(|_| ScalarField::zero());
+        for i in 0..N {
             let i_u8 = i as u8;
             // 1/(i-k)
             let inverse = match i_u8 {
-                i if i < k => -PrecomputedWeights::domain_inv(k - i),
+                i if i < k => -PrecomputedWeights::<N>::domain_inv(k - i),
                 i if i == k => continue,
-                i if i > k => PrecomputedWeights::domain_inv(i - k).clone(),
+                i if i > k => PrecomputedWeights::<N>::domain_inv(i - k).clone(),
                 _ => unreachable!(),
             };
 
             // Q(i) = (y_i-y_k) / (i-k)
             q[i] = (&self.y[i] - &self.y[k_usize]) * inverse;
 
-            q[k_usize] -=
-                &q[i] * PrecomputedWeights::a_prime(k) * PrecomputedWeights::a_prime_inv(i_u8);
+            q[k_usize] -= &q[i]
+                * PrecomputedWeights::<N>::a_prime(k)
+                * PrecomputedWeights::<N>::a_prime_inv(i_u8);
         }
 
         Self::new(q)
     }
 }
 
-impl From<&[ScalarField]> for LagrangeBasis {
+impl<const N: usize> From<&[ScalarField]> for LagrangeBasis<N> {
     fn from(other: &[ScalarField]) -> Self {
-        assert!(other.len() == VERKLE_NODE_WIDTH);
-        Self {
-            y: array_long(|i| other[i as usize].clone()),
-        }
+        assert!(other.len() == N);
+        Self::new(array::from_fn(|i| other[i].clone()))
+    }
+}
+
+impl<const N: usize> AddAssign<&Self> for LagrangeBasis<N> {
+    fn add_assign(&mut self, rhs: &Self) {
+        self.y
+            .iter_mut()
+            .zip_eq(&rhs.y)
+            .for_each(|(lhs, rhs)| *lhs += rhs)
+    }
+}
+
+impl<const N: usize> AddAssign for LagrangeBasis<N> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self += &rhs
+    }
+}
+
+impl<const N: usize> Add<&Self> for LagrangeBasis<N> {
+    type Output = Self;
+
+    fn add(mut self, rhs: &Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl<const N: usize> Add for LagrangeBasis<N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self + &rhs
+    }
+}
+
+impl<const N: usize> SubAssign<&Self> for LagrangeBasis<N> {
+    fn sub_assign(&mut self, rhs: &Self) {
+        self.y
+            .iter_mut()
+            .zip_eq(&rhs.y)
+            .for_each(|(lhs, rhs)| *lhs -= rhs)
+    }
+}
+
+impl<const N: usize> SubAssign for LagrangeBasis<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self -= &rhs
+    }
+}
+
+impl<const N: usize> Sub<&Self> for LagrangeBasis<N> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: &Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl<const N: usize> Sub for LagrangeBasis<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self - &rhs
+    }
+}
+
+impl<const N: usize> MulAssign<&ScalarField> for LagrangeBasis<N> {
+    fn mul_assign(&mut self, rhs: &ScalarField) {
+        self.y.iter_mut().for_each(|lhs| *lhs *= rhs)
+    }
+}
+
+impl<const N: usize> MulAssign<ScalarField> for LagrangeBasis<N> {
+    fn mul_assign(&mut self, rhs: ScalarField) {
+        *self *= &rhs
+    }
+}
+
+impl<const N: usize> Mul<&ScalarField> for LagrangeBasis<N> {
+    type Output = Self;
+
+    fn mul(mut self, rhs: &ScalarField) -> Self {
+        self *= rhs;
+        self
+    }
+}
+
+impl<const N: usize> Mul<ScalarField> for LagrangeBasis<N> {
+    type Output = Self;
+
+    fn mul(self, rhs: ScalarField) -> Self {
+        self * &rhs
     }
 }
 
-overload!((lhs: &mut LagrangeBasis) += (rhs: ?LagrangeBasis) {
-    lhs.y.iter_mut().zip_eq(&rhs.y).for_each(|(lhs, rhs)| *lhs += rhs)
-});
-overload!((lhs: LagrangeBasis) + (rhs: ?LagrangeBasis) -> LagrangeBasis {
-    let mut lhs = lhs; lhs += rhs; lhs
-});
-
-overload!((lhs: &mut LagrangeBasis) -= (rhs: ?LagrangeBasis) {
-    lhs.y.iter_mut().zip_eq(&rhs.y).for_each(|(lhs, rhs)| *lhs -= rhs)
-});
-overload!((lhs: LagrangeBasis) - (rhs: ?LagrangeBasis) -> LagrangeBasis {
-    let mut lhs = lhs; lhs -= rhs; lhs
-});
-
-overload!((lhs: &mut LagrangeBasis) *= (rhs: ScalarField) {
-    lhs.y.iter_mut().for_each(|lhs| *lhs *= &rhs)
-});
-overload!((lhs: &mut LagrangeBasis) *= (rhs: &ScalarField) {
-    lhs.y.iter_mut().for_each(|lhs| *lhs *= rhs)
-});
-overload!((lhs: LagrangeBasis) * (rhs: ?ScalarField) -> LagrangeBasis {
-    let mut lhs = lhs; lhs *= rhs; lhs
-});
-
-impl Sum for LagrangeBasis {
+impl<const N: usize> Sum for LagrangeBasis<N> {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.reduce(|a, b| a + b).unwrap_or_else(Self::zero)
     }
 }
 
-impl<'a> Sum<&'a LagrangeBasis> for LagrangeBasis {
+impl<'a, const N: usize> Sum<&'a LagrangeBasis<N>> for LagrangeBasis<N> {
     fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
-        iter.fold(LagrangeBasis::zero(), |sum, poly| sum + poly)
+        iter.fold(Self::zero(), |sum, poly| sum + poly)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "outside of the domain")]
+    fn divide_on_domain_outside_of_domain() {
+        let poly = LagrangeBasis::<16>::new(array::from_fn(ScalarField::from));
+        poly.divide_on_domain(16);
+    }
+
+    #[test]
+    fn smaller_domain() {
+        // P(x) = x^2 + 1
+        let eval = |x: &ScalarField| x * x + ScalarField::one();
+        let poly = LagrangeBasis::<16>::new(array::from_fn(|i| eval(&ScalarField::from(i))));
+
+        let z = ScalarField::from(1234u64);
+        assert_eq!(poly.evaluate_outside_domain(&z), eval(&z));
+        assert_eq!(
+            poly.commit(),
+            CRS::commit_sparse(
+                &(0..16)
+                    .map(|i| (i, eval(&ScalarField::from(i))))
+                    .collect_vec()
+            )
+        );
+
+        // Q(x) = (P(x) - P(3)) / (x - 3) = x + 3
+        let q = poly.divide_on_domain(3);
+        assert_eq!(
+            q.evaluations(),
+            &array::from_fn(|i| ScalarField::from(i + 3))
+        );
+    }
+
+    #[test]
+    fn monomial_conversion() {
+        // P(x) = 1 + 2x + 3x^2
+        let p = MonomialBasis::new(
+            vec![1u64, 2, 3]
+                .into_iter()
+                .map(ScalarField::from)
+                .collect(),
+        );
+        let poly = LagrangeBasis::<16>::from_monomial(&p).unwrap();
+        assert_eq!(poly.to_monomial(), p);
+        assert_eq!(poly.degree(), Some(2));
+        assert_eq!(LagrangeBasis::<16>::zero().degree(), None);
+
+        let z = ScalarField::from(1234u64);
+        assert_eq!(poly.evaluate(&z), p.evaluate(&z));
+        assert_eq!(
+            poly.evaluate(&ScalarField::from(5u64)),
+            p.evaluate(&ScalarField::from(5u64))
+        );
+
+        // Polynomial of degree 16 can't be represented with 16 evaluations
+        let a = MonomialBasis::vanishing(&array::from_fn::<_, 16, _>(ScalarField::from));
+        assert!(LagrangeBasis::<16>::from_monomial(&a).is_none());
+    }
+
+    #[test]
+    fn lagrange_polynomials() {
+        // The L_i(x) has value 1 at i and 0 elsewhere on domain
+        let z = ScalarField::from(1234u64);
+        let expected = PrecomputedWeights::<16>::evaluate_lagrange_polynomials(&z);
+        for (i, expected) in expected.iter().enumerate() {
+            let mut y = array::from_fn(|_| ScalarField::zero());
+            y[i] = ScalarField::one();
+            let l_i = LagrangeBasis::<16>::new(y).to_monomial();
+            assert_eq!(l_i.degree(), Some(15));
+            assert_eq!(&l_i.evaluate(&z), expected);
+        }
+    }
+
+    #[test]
+    fn checked_mul() {
+        // P(x) = x + 1
+        let p = LagrangeBasis::<16>::new(array::from_fn(|i| ScalarField::from(i + 1)));
+        // P(x)^8
+        let p8 = (0..3).fold(p.clone(), |p, _| p.checked_mul(&p).unwrap());
+        assert_eq!(p8.degree(), Some(8));
+        let z = ScalarField::from(1234u64);
+        assert_eq!(
+            p8.evaluate(&z),
+            ScalarField::powers_of(&(&z + ScalarField::one()), 9)[8]
+        );
+
+        // P(x)^16 has too high degree
+        assert!(p8.checked_mul(&p8).is_none());
+        assert_eq!(
+            p8.checked_mul(&LagrangeBasis::zero()).unwrap().degree(),
+            None
+        );
+    }
+
+    #[test]
+    fn interpolate() {
+        // P(x) = 1 + 2x + 3x^2
+        let p = MonomialBasis::new(
+            vec![1u64, 2, 3]
+                .into_iter()
+                .map(ScalarField::from)
+                .collect(),
+        );
+        let points = [1234u64, 7, 1_000_000]
+            .map(ScalarField::from)
+            .map(|x| (x.clone(), p.evaluate(&x)));
+        assert_eq!(
+            LagrangeBasis::<16>::interpolate(&points)
+                .unwrap()
+                .to_monomial(),
+            p
+        );
     }
 }
@@ -2,16 +2,20 @@ use derive_more::{Constructor, Deref};
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 
+pub use error::ProofError;
 pub use ipa::*;
 pub use multiproof::*;
 pub use prover_query::*;
 pub use verifier_query::*;
 
+mod error;
 mod ipa;
 pub mod lagrange_basis;
+pub mod monomial_basis;
 mod multiproof;
 pub mod precomputed_weights;
 mod prover_query;
+pub mod recording_transcript;
 pub mod transcript;
 mod verifier_query;
 
@@ -0,0 +1,241 @@
+use std::ops;
+
+use itertools::{zip_eq, Itertools};
+use overload::overload;
+
+use crate::{BatchInversion, ScalarField};
+
+/// The polynomial expressed using coefficient (monomial) basis:
+///
+/// ```text
+/// P(x) = c_0 + c_1 * x + ... + c_n * x^n
+/// ```
+///
+/// Unlike [LagrangeBasis](super::lagrange_basis::LagrangeBasis), it's not bound to the domain, so
+/// it can represent polynomial of any degree. Trailing zero coefficients are not stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MonomialBasis {
+    coefficients: Vec<ScalarField>,
+}
+
+impl MonomialBasis {
+    /// Creates the polynomial from its coefficients, starting with the constant term (`c_0`).
+    pub fn new(coefficients: Vec<ScalarField>) -> Self {
+        let mut result = Self { coefficients };
+        result.trim();
+        result
+    }
+
+    pub fn zero() -> Self {
+        Self::new(vec![])
+    }
+
+    /// Returns the polynomial that has given roots: `A(x) = ∏ (x - x_i)`.
+    pub fn vanishing(roots: &[ScalarField]) -> Self {
+        roots
+            .iter()
+            .fold(Self::new(vec![ScalarField::one()]), |a, root| {
+                a * Self::new(vec![-root, ScalarField::one()])
+            })
+    }
+
+    /// Interpolates the polynomial of the lowest degree that goes through all given points
+    /// (`(x_i, y_i)`).
+    ///
+    /// ```text
+    ///                   A(x)
+    /// P(x) = ∑ y_i * ----------------
+    ///        i        A'(x_i) * (x-x_i)
+    /// ```
+    ///
+    /// Returns `None` if the same `x_i` is present more than once.
+    pub fn interpolate(points: &[(ScalarField, ScalarField)]) -> Option<Self> {
+        let xs = points.iter().map(|(x, _)| x.clone()).collect_vec();
+        let a = Self::vanishing(&xs);
+
+        // A(x) / (x-x_i)
+        let numerators = xs.iter().map(|x| a.divide_by_root(x)).collect_vec();
+
+        // A'(x_i) = ∏ (x_i-x_j)
+        //          j≠i
+        let denominators = zip_eq(&numerators, &xs)
+            .map(|(numerator, x)| numerator.evaluate(x))
+            .collect_vec();
+        if denominators.iter().any(ScalarField::is_zero) {
+            return None;
+        }
+        let denominators_inv = denominators.batch_inverse();
+
+        Some(
+            numerators
+                .into_iter()
+                .zip_eq(denominators_inv)
+                .zip_eq(points)
+                .map(|((numerator, denominator_inv), (_, y))| numerator * (denominator_inv * y))
+                .fold(Self::zero(), |sum, poly| sum + poly),
+        )
+    }
+
+    /// Returns coefficients, starting with the constant term (`c_0`).
+    pub fn coefficients(&self) -> &[ScalarField] {
+        &self.coefficients
+    }
+
+    /// Returns the degree of the polynomial, or `None` for zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    /// Calculates `P(z)`, using Horner's method.
+    pub fn evaluate(&self, z: &ScalarField) -> ScalarField {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(ScalarField::zero(), |result, coefficient| {
+                result * z + coefficient
+            })
+    }
+
+    /// Divides the polynomial `P(x)` with `x-r`, ignoring the remainder (which is zero if `r` is
+    /// root of the polynomial).
+    pub fn divide_by_root(&self, r: &ScalarField) -> Self {
+        let Some(degree) = self.degree() else {
+            return Self::zero();
+        };
+
+        // Synthetic division: q_(i-1) = c_i + r * q_i
+        let mut quotient = vec![ScalarField::zero(); degree];
+        let mut carry = ScalarField::zero();
+        for i in (1..=degree).rev() {
+            carry = &self.coefficients[i] + carry * r;
+            quotient[i - 1] = carry.clone();
+        }
+        Self::new(quotient)
+    }
+
+    fn trim(&mut self) {
+        while self.coefficients.last().is_some_and(ScalarField::is_zero) {
+            self.coefficients.pop();
+        }
+    }
+}
+
+overload!((lhs: &mut MonomialBasis) += (rhs: ?MonomialBasis) {
+    if lhs.coefficients.len() < rhs.coefficients.len() {
+        lhs.coefficients.resize(rhs.coefficients.len(), ScalarField::zero());
+    }
+    lhs.coefficients.iter_mut().zip(&rhs.coefficients).for_each(|(lhs, rhs)| *lhs += rhs);
+    lhs.trim();
+});
+overload!((lhs: MonomialBasis) + (rhs: ?MonomialBasis) -> MonomialBasis {
+    let mut lhs = lhs; lhs += rhs; lhs
+});
+
+overload!((lhs: &mut MonomialBasis) -= (rhs: ?MonomialBasis) {
+    if lhs.coefficients.len() < rhs.coefficients.len() {
+        lhs.coefficients.resize(rhs.coefficients.len(), ScalarField::zero());
+    }
+    lhs.coefficients.iter_mut().zip(&rhs.coefficients).for_each(|(lhs, rhs)| *lhs -= rhs);
+    lhs.trim();
+});
+overload!((lhs: MonomialBasis) - (rhs: ?MonomialBasis) -> MonomialBasis {
+    let mut lhs = lhs; lhs -= rhs; lhs
+});
+
+overload!((lhs: ?MonomialBasis) * (rhs: ?MonomialBasis) -> MonomialBasis {
+    if lhs.coefficients.is_empty() || rhs.coefficients.is_empty() {
+        return MonomialBasis::zero();
+    }
+    let mut result =
+        vec![ScalarField::zero(); lhs.coefficients.len() + rhs.coefficients.len() - 1];
+    for (i, lhs) in lhs.coefficients.iter().enumerate() {
+        for (j, rhs) in rhs.coefficients.iter().enumerate() {
+            result[i + j] += lhs * rhs;
+        }
+    }
+    MonomialBasis::new(result)
+});
+
+overload!((lhs: &mut MonomialBasis) *= (rhs: ScalarField) {
+    *lhs *= &rhs
+});
+overload!((lhs: &mut MonomialBasis) *= (rhs: &ScalarField) {
+    lhs.coefficients.iter_mut().for_each(|lhs| *lhs *= rhs);
+    lhs.trim();
+});
+overload!((lhs: MonomialBasis) * (rhs: ?ScalarField) -> MonomialBasis {
+    let mut lhs = lhs; lhs *= rhs; lhs
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poly(coefficients: &[u64]) -> MonomialBasis {
+        MonomialBasis::new(coefficients.iter().map(|c| ScalarField::from(*c)).collect())
+    }
+
+    #[test]
+    fn evaluate() {
+        // P(x) = 3 + 2x + x^2
+        let p = poly(&[3, 2, 1]);
+        assert_eq!(p.degree(), Some(2));
+        assert_eq!(
+            p.evaluate(&ScalarField::from(10u64)),
+            ScalarField::from(123u64)
+        );
+        assert_eq!(MonomialBasis::zero().degree(), None);
+        assert_eq!(poly(&[0, 0]), MonomialBasis::zero());
+    }
+
+    #[test]
+    fn arithmetic() {
+        // (1 + x) * (1 - x) = 1 - x^2
+        let one_plus_x = poly(&[1, 1]);
+        let one_minus_x = poly(&[1]) - poly(&[0, 1]);
+        assert_eq!(
+            one_plus_x.clone() * one_minus_x.clone(),
+            poly(&[1]) - poly(&[0, 0, 1])
+        );
+        assert_eq!(one_plus_x + one_minus_x, poly(&[2]));
+        assert_eq!(poly(&[1, 2]) - poly(&[1, 2]), MonomialBasis::zero());
+        assert_eq!(poly(&[1, 2]) * ScalarField::from(3u64), poly(&[3, 6]));
+    }
+
+    #[test]
+    fn vanishing() {
+        let roots = [1u64, 5, 7].map(ScalarField::from);
+        let a = MonomialBasis::vanishing(&roots);
+        assert_eq!(a.degree(), Some(3));
+        for root in &roots {
+            assert!(a.evaluate(root).is_zero());
+        }
+        assert_eq!(
+            a.divide_by_root(&roots[0]),
+            MonomialBasis::vanishing(&roots[1..])
+        );
+    }
+
+    #[test]
+    fn interpolate() {
+        // P(x) = 3 + 2x + x^2
+        let p = poly(&[3, 2, 1]);
+        let points = [100u64, 2, 1234]
+            .map(ScalarField::from)
+            .map(|x| (x.clone(), p.evaluate(&x)));
+        assert_eq!(MonomialBasis::interpolate(&points), Some(p));
+        assert_eq!(MonomialBasis::interpolate(&[]), Some(MonomialBasis::zero()));
+    }
+
+    #[test]
+    fn interpolate_duplicate_x() {
+        let x = ScalarField::from(1u64);
+        assert_eq!(
+            MonomialBasis::interpolate(&[
+                (x.clone(), ScalarField::from(2u64)),
+                (x, ScalarField::from(3u64)),
+            ]),
+            None
+        );
+    }
+}
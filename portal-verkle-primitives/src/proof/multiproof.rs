@@ -7,8 +7,10 @@ use ssz_derive::{Decode, Encode};
 use crate::{constants::VERKLE_NODE_WIDTH, BatchInversion, DotProduct, Point, ScalarField};
 
 use super::{
-    lagrange_basis::LagrangeBasis, transcript::Transcript, IpaProof, ProverMultiQuery,
-    VerifierMultiQuery,
+    ipa::{check_outside_of_domain, IpaVerificationEquation},
+    lagrange_basis::LagrangeBasis,
+    transcript::{Transcript, TranscriptProtocol},
+    IpaProof, ProofError, ProverMultiQuery, VerifierMultiQuery,
 };
 
 /// The multi-point proof based on IPA.
@@ -23,15 +25,67 @@ pub struct MultiProof {
     pub g_commitment: Point,
 }
 
+/// Determines how queries are processed before the proof is created or verified.
+///
+/// The same mode has to be used for creating and verifying the proof.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MultiQueryMode {
+    /// Queries are used in the provided order, including duplicates.
+    ///
+    /// This is compatible with all existing proofs (e.g. the Portal Network content).
+    #[default]
+    Compatible,
+    /// Duplicate queries are removed and remaining ones are sorted canonically (see
+    /// [ProverMultiQuery::deduplicate] and [VerifierMultiQuery::deduplicate]).
+    ///
+    /// This makes proof creation and verification cheaper when the same commitment is opened at
+    /// the same index more than once.
+    Deduplicated,
+}
+
 impl MultiProof {
-    pub fn create_portal_network_proof(multiquery: ProverMultiQuery) -> Self {
+    pub fn create_portal_network_proof(multiquery: ProverMultiQuery) -> Result<Self, ProofError> {
         Self::create(
             multiquery,
             &mut Transcript::new(Transcript::PORTAL_NETWORK_LABEL),
         )
     }
 
-    pub fn create(multiquery: ProverMultiQuery, transcript: &mut Transcript) -> Self {
+    /// Returns whether this is the proof created for the empty multiquery.
+    ///
+    /// All elements of such proof are zero.
+    pub fn is_empty(&self) -> bool {
+        self.g_commitment.is_zero()
+            && self
+                .ipa_proof
+                .cl
+                .iter()
+                .chain(&self.ipa_proof.cr)
+                .all(Point::is_zero)
+            && self.ipa_proof.final_evaluation.is_zero()
+    }
+
+    /// Creates the proof for all queries, using [MultiQueryMode::Compatible].
+    ///
+    /// Proof for the empty multiquery can be created (e.g. for the trie path of the root node),
+    /// but it doesn't prove anything and [verify](Self::verify) rejects it.
+    pub fn create(
+        multiquery: ProverMultiQuery,
+        transcript: &mut impl TranscriptProtocol,
+    ) -> Result<Self, ProofError> {
+        Self::create_with_mode(multiquery, transcript, MultiQueryMode::Compatible)
+    }
+
+    /// Creates the proof for all queries, processed according to the mode.
+    pub fn create_with_mode(
+        mut multiquery: ProverMultiQuery,
+        transcript: &mut impl TranscriptProtocol,
+        mode: MultiQueryMode,
+    ) -> Result<Self, ProofError> {
+        if mode == MultiQueryMode::Deduplicated {
+            multiquery.deduplicate();
+        }
+
         transcript.domain_sep("multiproof");
 
         // 1. Compute r
@@ -75,7 +129,7 @@ impl MultiProof {
         let t = transcript.challenge_scalar("t");
 
         // NOTE: Check that t is not in the domain!
-        assert!(t >= ScalarField::from(VERKLE_NODE_WIDTH));
+        check_outside_of_domain::<VERKLE_NODE_WIDTH>(&t)?;
 
         // 5. Compute h(x)
         // h(x) = ∑ r^i * f_i(x) / (t - z_i)
@@ -103,22 +157,87 @@ impl MultiProof {
         let p_comm = e - &d;
 
         // 8. Create IPA proof for polynomial p(x) at point t
-        let ipa = IpaProof::open_polynomial(Some(p_comm), p, t, transcript);
+        let ipa = IpaProof::open_polynomial(Some(p_comm), p, t, transcript)?;
 
-        Self {
+        Ok(Self {
             ipa_proof: ipa,
             g_commitment: d,
-        }
+        })
     }
 
-    pub fn verify_portal_network_proof(&self, multiquery: VerifierMultiQuery) -> bool {
+    pub fn verify_portal_network_proof(
+        &self,
+        multiquery: VerifierMultiQuery,
+    ) -> Result<(), ProofError> {
         self.verify(
             multiquery,
             &mut Transcript::new(Transcript::PORTAL_NETWORK_LABEL),
         )
     }
 
-    pub fn verify(&self, multiquery: VerifierMultiQuery, transcript: &mut Transcript) -> bool {
+    /// Verifies the proof for all queries, using [MultiQueryMode::Compatible].
+    pub fn verify(
+        &self,
+        multiquery: VerifierMultiQuery,
+        transcript: &mut impl TranscriptProtocol,
+    ) -> Result<(), ProofError> {
+        self.verify_with_mode(multiquery, transcript, MultiQueryMode::Compatible)
+    }
+
+    /// Verifies the proof for all queries, processed according to the mode.
+    pub fn verify_with_mode(
+        &self,
+        mut multiquery: VerifierMultiQuery,
+        transcript: &mut impl TranscriptProtocol,
+        mode: MultiQueryMode,
+    ) -> Result<(), ProofError> {
+        if mode == MultiQueryMode::Deduplicated {
+            multiquery.deduplicate();
+        }
+        self.verification_equation(multiquery, transcript)?.check()
+    }
+
+    /// Same as [verify_batch](Self::verify_batch), but uses Portal Network transcript for every
+    /// proof.
+    pub fn verify_portal_network_proof_batch<'a>(
+        proofs: impl IntoIterator<Item = (&'a Self, VerifierMultiQuery)>,
+    ) -> Result<(), ProofError> {
+        Self::verify_batch(proofs.into_iter().map(|(proof, multiquery)| {
+            (
+                proof,
+                multiquery,
+                Transcript::new(Transcript::PORTAL_NETWORK_LABEL),
+            )
+        }))
+    }
+
+    /// Verifies many proofs at once.
+    ///
+    /// Succeeds only if all proofs are valid. This is significantly faster than verifying each
+    /// proof individually, because final checks of all IPA proofs are combined into one
+    /// multi-scalar multiplication. However, if final check fails, it doesn't tell which proof is
+    /// invalid.
+    pub fn verify_batch<'a, T: TranscriptProtocol>(
+        proofs: impl IntoIterator<Item = (&'a Self, VerifierMultiQuery, T)>,
+    ) -> Result<(), ProofError> {
+        let equations = proofs
+            .into_iter()
+            .map(|(proof, multiquery, mut transcript)| {
+                proof.verification_equation(multiquery, &mut transcript)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        IpaVerificationEquation::check_all(&equations)
+    }
+
+    fn verification_equation(
+        &self,
+        multiquery: VerifierMultiQuery,
+        transcript: &mut impl TranscriptProtocol,
+    ) -> Result<IpaVerificationEquation<VERKLE_NODE_WIDTH>, ProofError> {
+        if multiquery.is_empty() {
+            return Err(ProofError::EmptyMultiQuery);
+        }
+
         transcript.domain_sep("multiproof");
 
         // Extract D (commitment to g(x): D = [g(x)])
@@ -137,6 +256,7 @@ impl MultiProof {
         // t = H(r, D)
         transcript.append_point("D", d);
         let t = transcript.challenge_scalar("t");
+        check_outside_of_domain::<VERKLE_NODE_WIDTH>(&t)?;
 
         // 3. Compute coefficients coeff_i
         // coeff_i = r^i / (t - z_i)
@@ -173,7 +293,8 @@ impl MultiProof {
         let p_comm = e - d;
 
         // 4. Verify IPA proof
-        self.ipa_proof.verify_polynomial(p_comm, t, p_t, transcript)
+        self.ipa_proof
+            .verification_equation(p_comm, t, p_t, transcript)
     }
 }
 
@@ -228,7 +349,7 @@ mod tests {
                 }
             })
             .collect();
-        let proof = MultiProof::create(queries, &mut transcript);
+        let proof = MultiProof::create(queries, &mut transcript).unwrap();
 
         let mut transcript = Transcript::new("test");
         let queries = openings
@@ -239,7 +360,197 @@ mod tests {
                 y: poly_f(*z),
             })
             .collect();
-        assert!(proof.verify(queries, &mut transcript));
+        assert!(proof.verify(queries, &mut transcript).is_ok());
+    }
+
+    #[test]
+    fn deduplicated_mode() {
+        let f = |x: u8| ScalarField::from(x) * ScalarField::from(x);
+        let poly = LagrangeBasis::new(array_long(f));
+        let commitment = poly.commit();
+        let zs = [5, 1, 5, 200, 1, 5];
+
+        let mut prover_queries = ProverMultiQuery::new();
+        prover_queries.add_vector(commitment.clone(), poly, zs);
+        let mut verifier_queries = VerifierMultiQuery::new();
+        verifier_queries.add_for_commitment(&commitment, zs.map(|z| (z, f(z))));
+
+        let proof = MultiProof::create_with_mode(
+            prover_queries.clone(),
+            &mut Transcript::new("test"),
+            MultiQueryMode::Deduplicated,
+        )
+        .unwrap();
+
+        // Order of queries doesn't matter
+        let mut reversed_verifier_queries = verifier_queries.clone();
+        reversed_verifier_queries.reverse();
+        assert!(proof
+            .verify_with_mode(
+                reversed_verifier_queries,
+                &mut Transcript::new("test"),
+                MultiQueryMode::Deduplicated
+            )
+            .is_ok());
+
+        // Same proof as if deduplicated queries were provided
+        let mut deduplicated_prover_queries = prover_queries.clone();
+        deduplicated_prover_queries.deduplicate();
+        assert_eq!(deduplicated_prover_queries.len(), 3);
+        assert_eq!(
+            MultiProof::create(deduplicated_prover_queries, &mut Transcript::new("test")).unwrap(),
+            proof
+        );
+
+        // Modes are not compatible
+        assert!(matches!(
+            proof.verify(verifier_queries.clone(), &mut Transcript::new("test")),
+            Err(ProofError::VerificationFailed)
+        ));
+        let compatible_proof =
+            MultiProof::create(prover_queries, &mut Transcript::new("test")).unwrap();
+        assert!(compatible_proof
+            .verify(verifier_queries, &mut Transcript::new("test"))
+            .is_ok());
+    }
+
+    #[test]
+    fn deduplicate_keeps_different_values() {
+        let f = |x: u8| ScalarField::from(x) + ScalarField::one();
+        let poly = LagrangeBasis::new(array_long(f));
+        let commitment = poly.commit();
+
+        let mut prover_queries = ProverMultiQuery::new();
+        prover_queries.add_vector(commitment.clone(), poly, [7, 7]);
+        let proof = MultiProof::create_with_mode(
+            prover_queries,
+            &mut Transcript::new("test"),
+            MultiQueryMode::Deduplicated,
+        )
+        .unwrap();
+
+        let mut verifier_queries = VerifierMultiQuery::new();
+        verifier_queries.add_for_commitment(&commitment, [(7, f(7)), (7, f(8))]);
+        let mut deduplicated_verifier_queries = verifier_queries.clone();
+        deduplicated_verifier_queries.deduplicate();
+        assert_eq!(deduplicated_verifier_queries.len(), 2);
+        assert!(matches!(
+            proof.verify_with_mode(
+                verifier_queries,
+                &mut Transcript::new("test"),
+                MultiQueryMode::Deduplicated
+            ),
+            Err(ProofError::VerificationFailed)
+        ));
+    }
+
+    /// Creates the proof that polynomial `f(x) = seed * (x + 1)` evaluates correctly at given
+    /// points, together with corresponding verifier queries.
+    fn create_proof(seed: u64, zs: &[u8]) -> (MultiProof, VerifierMultiQuery) {
+        let f = |x: u8| ScalarField::from(seed) * (ScalarField::from(x) + ScalarField::one());
+        let poly = LagrangeBasis::new(array_long(f));
+        let commitment = poly.commit();
+
+        let prover_queries = zs
+            .iter()
+            .map(|z| ProverQuery {
+                poly: poly.clone(),
+                commitment: Some(commitment.clone()),
+                z: *z,
+            })
+            .collect();
+        let proof = MultiProof::create_portal_network_proof(prover_queries).unwrap();
+        let verifier_queries = zs
+            .iter()
+            .map(|z| VerifierQuery {
+                commitment: commitment.clone(),
+                z: *z,
+                y: f(*z),
+            })
+            .collect();
+        (proof, verifier_queries)
+    }
+
+    #[test]
+    fn verify_batch() {
+        let proofs = [
+            create_proof(1, &[0]),
+            create_proof(2, &[1, 2, 3]),
+            create_proof(3, &[255, 0, 255]),
+        ];
+        assert!(MultiProof::verify_portal_network_proof_batch(
+            proofs
+                .iter()
+                .map(|(proof, queries)| (proof, queries.clone()))
+        )
+        .is_ok());
+        assert!(MultiProof::verify_portal_network_proof_batch([]).is_ok());
+    }
+
+    #[test]
+    fn verify_batch_with_invalid_proof() {
+        let proofs = [
+            create_proof(1, &[0]),
+            create_proof(2, &[1, 2, 3]),
+            create_proof(3, &[255, 0, 255]),
+        ];
+
+        // Wrong evaluation
+        let mut wrong_queries = proofs[1].1.clone();
+        wrong_queries[1].y += ScalarField::one();
+        assert!(matches!(
+            MultiProof::verify_portal_network_proof_batch([
+                (&proofs[0].0, proofs[0].1.clone()),
+                (&proofs[1].0, wrong_queries),
+                (&proofs[2].0, proofs[2].1.clone()),
+            ]),
+            Err(ProofError::VerificationFailed)
+        ));
+
+        // Proofs swapped
+        assert!(matches!(
+            MultiProof::verify_portal_network_proof_batch([
+                (&proofs[0].0, proofs[0].1.clone()),
+                (&proofs[2].0, proofs[1].1.clone()),
+                (&proofs[1].0, proofs[2].1.clone()),
+            ]),
+            Err(ProofError::VerificationFailed)
+        ));
+
+        // Wrong transcript
+        assert!(matches!(
+            MultiProof::verify_batch([
+                (
+                    &proofs[0].0,
+                    proofs[0].1.clone(),
+                    Transcript::new(Transcript::PORTAL_NETWORK_LABEL)
+                ),
+                (
+                    &proofs[1].0,
+                    proofs[1].1.clone(),
+                    Transcript::new(Transcript::VERKLE_TRIE_LABEL)
+                ),
+            ]),
+            Err(ProofError::VerificationFailed)
+        ));
+
+        // Empty multiquery
+        assert!(matches!(
+            MultiProof::verify_portal_network_proof_batch([
+                (&proofs[0].0, proofs[0].1.clone()),
+                (&proofs[1].0, VerifierMultiQuery::new()),
+            ]),
+            Err(ProofError::EmptyMultiQuery)
+        ));
+    }
+
+    #[test]
+    fn empty_multiquery() {
+        let proof = MultiProof::create_portal_network_proof(ProverMultiQuery::new()).unwrap();
+        assert!(matches!(
+            proof.verify_portal_network_proof(VerifierMultiQuery::new()),
+            Err(ProofError::EmptyMultiQuery)
+        ));
     }
 
     /// Proof from the kaustinen devnet-6, block 1
@@ -270,17 +581,17 @@ mod tests {
         }"#;
         let proof = MultiProof {
             ipa_proof: serde_json::from_str::<IpaProof>(ipa_proof)?,
-            g_commitment: Point::from(&B256::from_hex(
+            g_commitment: Point::try_from(&B256::from_hex(
                 "0x5af46ab3e8676b9d4de8ae0be9670c45e9afd43cc11524c7946728268652028a",
-            )?),
+            )?)?,
         };
 
-        let root = Point::from(&B256::from_hex(
+        let root = Point::try_from(&B256::from_hex(
             "0x1fbf85345a3cbba9a6d44f991b721e55620a22397c2a93ee8d5011136ac300ee",
-        )?);
-        let other_leaf = Point::from(&B256::from_hex(
+        )?)?;
+        let other_leaf = Point::try_from(&B256::from_hex(
             "0x26715ff22c071fdd9d9c2c6b5f5bf9bb0d83a2087e1366deabcecdf2a1d3f82e",
-        )?);
+        )?)?;
         let other_stem =
             Stem::from_hex("0x5bdf12f5e17d2911dac2d2b0fc9e64a3ddc1d1ea4fc2568fe7e741ff2daa18")?;
 
@@ -303,10 +614,7 @@ mod tests {
             },
         ]);
         let mut transcript = Transcript::new("vt");
-        assert!(
-            proof.verify(queries, &mut transcript),
-            "Multiproof should verify"
-        );
+        proof.verify(queries, &mut transcript)?;
         Ok(())
     }
 }
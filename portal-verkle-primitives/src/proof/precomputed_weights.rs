@@ -1,10 +1,16 @@
+use std::{any::Any, array};
+
 use once_cell::sync::Lazy;
 
-use crate::{constants::VERKLE_NODE_WIDTH, utils::array_long, BatchInversion, ScalarField};
+use crate::{
+    constants::VERKLE_NODE_WIDTH,
+    utils::{generic_static, DomainSize},
+    BatchInversion, ScalarField,
+};
 
 /// Precomputed weights for Lagrange polynomial (`L_i`) related calculations.
 ///
-/// Domain `D` is `[0, 255]` => `d = 256``.
+/// Domain `D` is `[0, N-1]` => `d = N` (for the Verkle trie, `D` is `[0, 255]`).
 /// ```text
 /// Lagrange polynomials:
 ///              x-j
@@ -26,36 +32,38 @@ use crate::{constants::VERKLE_NODE_WIDTH, utils::array_long, BatchInversion, Sca
 /// L_i(x) = ∏  ----- = ---------------
 ///         j≠i  i-j     A'(i) * (x-i)
 /// ```
-pub struct PrecomputedWeights {
+pub struct PrecomputedWeights<const N: usize = VERKLE_NODE_WIDTH> {
     /// The `A'(i)`, for i in domain
     ///
     /// ```text
     /// A'(i) =  ∏ (i-j)
     ///         j≠i
     /// ```
-    a_prime: [ScalarField; VERKLE_NODE_WIDTH],
+    a_prime: [ScalarField; N],
     /// The `1/A'(i)` , for i in domain
-    a_prime_inv: [ScalarField; VERKLE_NODE_WIDTH],
+    a_prime_inv: [ScalarField; N],
     /// The `1/i` , for i in domain (except when i is zero, in which case value is zero)
-    domain_inv: [ScalarField; VERKLE_NODE_WIDTH],
+    domain_inv: [ScalarField; N],
 }
 
 static INSTANCE: Lazy<PrecomputedWeights> = Lazy::new(PrecomputedWeights::new);
 
-impl PrecomputedWeights {
+impl<const N: usize> PrecomputedWeights<N> {
     fn new() -> Self {
-        let a_prime = array_long(|i| {
+        let () = DomainSize::<N>::CHECK;
+
+        let a_prime = array::from_fn(|i| {
             //  ∏ (i-j)
             // j≠i
-            (0..VERKLE_NODE_WIDTH)
-                .filter(|j| i != *j as u8)
+            (0..N)
+                .filter(|j| i != *j)
                 .map(|j| ScalarField::from(i) - ScalarField::from(j))
                 .product()
         });
 
         let a_prime_inv = a_prime.clone().batch_inverse();
 
-        let domain_inv = array_long(ScalarField::from).batch_inverse();
+        let domain_inv = array::from_fn(ScalarField::from).batch_inverse();
 
         Self {
             a_prime,
@@ -64,28 +72,34 @@ impl PrecomputedWeights {
         }
     }
 
+    fn instance() -> &'static Self {
+        // Avoid locking for the Verkle trie weights
+        match (&*INSTANCE as &dyn Any).downcast_ref() {
+            Some(instance) => instance,
+            None => generic_static(Self::new),
+        }
+    }
+
     /// Evaluates polynomial `A` at a given point `z`
     ///
     /// `A(z) = ∏ (z - i) = (z-0)(z-1)...(z-d)`
     pub fn evaluate_a(z: &ScalarField) -> ScalarField {
-        (0..VERKLE_NODE_WIDTH)
-            .map(|i| z - ScalarField::from(i))
-            .product()
+        (0..N).map(|i| z - ScalarField::from(i)).product()
     }
 
     /// Returns `A'(i)` for i in domain
     pub fn a_prime(i: u8) -> &'static ScalarField {
-        &INSTANCE.a_prime[i as usize]
+        &Self::instance().a_prime[i as usize]
     }
 
     /// Returns `1/A'(i)` for i in domain
     pub fn a_prime_inv(i: u8) -> &'static ScalarField {
-        &INSTANCE.a_prime_inv[i as usize]
+        &Self::instance().a_prime_inv[i as usize]
     }
 
     pub fn domain_inv(i: u8) -> &'static ScalarField {
         assert_ne!(i, 0);
-        &INSTANCE.domain_inv[i as usize]
+        &Self::instance().domain_inv[i as usize]
     }
 
     /// Evaluates Lagrange polynomials `L_i` at a given point `z`, using barycentric formula.
@@ -95,12 +109,13 @@ impl PrecomputedWeights {
     /// L_i(z) = ∏  ------- = -----------------
     ///         j≠i  i - j     A'(i) * (z - i)
     /// ```
-    pub fn evaluate_lagrange_polynomials(z: &ScalarField) -> [ScalarField; VERKLE_NODE_WIDTH] {
+    pub fn evaluate_lagrange_polynomials(z: &ScalarField) -> [ScalarField; N] {
         // A(z) = (z-0)(z-1)(z-2)...(z-d)
         let a_z = Self::evaluate_a(z);
 
         // A'(i) * (z-i)
-        let lagrange_evaluations = array_long(|i| (z - ScalarField::from(i)) * Self::a_prime(i));
+        let lagrange_evaluations =
+            array::from_fn(|i| (z - ScalarField::from(i)) * Self::a_prime(i as u8));
 
         // A(z) / (A'(i) * (z-i))
         lagrange_evaluations.batch_inverse_and_mul(&a_z)
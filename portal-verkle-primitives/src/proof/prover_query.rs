@@ -1,3 +1,4 @@
+use alloy_primitives::B256;
 use derive_more::{Deref, DerefMut, IntoIterator};
 
 use crate::Point;
@@ -47,6 +48,25 @@ impl ProverMultiQuery {
             z: child_index,
         }));
     }
+
+    /// Removes duplicate queries (same commitment and opening index) and sorts the remaining ones
+    /// canonically (by commitment and opening index).
+    ///
+    /// The [VerifierMultiQuery::deduplicate](super::VerifierMultiQuery::deduplicate) orders
+    /// queries the same way, so they should be used together.
+    pub fn deduplicate(&mut self) {
+        for query in self.iter_mut() {
+            query.commitment.get_or_insert_with(|| query.poly.commit());
+        }
+        self.sort_by_cached_key(|query| {
+            let commitment = query
+                .commitment
+                .as_ref()
+                .expect("commitment should be present");
+            (B256::from(commitment), query.z)
+        });
+        self.dedup_by(|a, b| a.commitment == b.commitment && a.z == b.z);
+    }
 }
 
 impl FromIterator<ProverQuery> for ProverMultiQuery {
@@ -0,0 +1,242 @@
+use std::fmt::{self, Display};
+
+use alloy_primitives::B256;
+
+use crate::{Point, ScalarField};
+
+use super::transcript::{Transcript, TranscriptProtocol};
+
+/// The single entry of the transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptEntry {
+    DomainSep(String),
+    Point {
+        label: String,
+        point: Point,
+    },
+    Scalar {
+        label: String,
+        scalar: ScalarField,
+    },
+    Challenge {
+        label: String,
+        challenge: ScalarField,
+    },
+}
+
+impl Display for TranscriptEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DomainSep(label) => write!(f, "domain_sep({label})"),
+            Self::Point { label, point } => write!(f, "point({label}, {})", B256::from(point)),
+            Self::Scalar { label, scalar } => write!(f, "scalar({label}, {scalar:?})"),
+            Self::Challenge { label, challenge } => {
+                write!(f, "challenge({label}) -> {challenge:?}")
+            }
+        }
+    }
+}
+
+/// The first entry at which two recordings differ.
+///
+/// The entry is `None` if that recording is shorter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptDivergence {
+    pub index: usize,
+    pub left: Option<TranscriptEntry>,
+    pub right: Option<TranscriptEntry>,
+}
+
+impl Display for TranscriptDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format_entry = |entry: &Option<TranscriptEntry>| match entry {
+            Some(entry) => entry.to_string(),
+            None => "<end>".to_string(),
+        };
+        write!(
+            f,
+            "transcripts diverge at entry {}: {} != {}",
+            self.index,
+            format_entry(&self.left),
+            format_entry(&self.right),
+        )
+    }
+}
+
+/// The transcript that records every entry (in order) and forwards it to the inner transcript.
+///
+/// Useful for debugging interoperability issues with other implementations, as it allows finding
+/// the first entry at which two transcripts diverge.
+pub struct RecordingTranscript<T = Transcript> {
+    inner: T,
+    entries: Vec<TranscriptEntry>,
+}
+
+impl RecordingTranscript {
+    /// Creates the recording of the [Transcript] with the given label.
+    pub fn new(label: &str) -> Self {
+        Self {
+            inner: Transcript::new(label),
+            entries: vec![TranscriptEntry::DomainSep(label.to_string())],
+        }
+    }
+}
+
+impl<T: TranscriptProtocol> RecordingTranscript<T> {
+    /// Records the transcript from this point onwards.
+    pub fn wrap(inner: T) -> Self {
+        Self {
+            inner,
+            entries: vec![],
+        }
+    }
+
+    pub fn entries(&self) -> &[TranscriptEntry] {
+        &self.entries
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns the first entry at which recordings differ, or `None` if they are the same.
+    pub fn diff<U>(&self, other: &RecordingTranscript<U>) -> Option<TranscriptDivergence> {
+        let index = self
+            .entries
+            .iter()
+            .zip(&other.entries)
+            .position(|(left, right)| left != right)
+            .unwrap_or(usize::min(self.entries.len(), other.entries.len()));
+        let divergence = TranscriptDivergence {
+            index,
+            left: self.entries.get(index).cloned(),
+            right: other.entries.get(index).cloned(),
+        };
+        if divergence.left.is_none() && divergence.right.is_none() {
+            None
+        } else {
+            Some(divergence)
+        }
+    }
+}
+
+impl<T: TranscriptProtocol> TranscriptProtocol for RecordingTranscript<T> {
+    fn domain_sep(&mut self, label: &str) {
+        self.entries
+            .push(TranscriptEntry::DomainSep(label.to_string()));
+        self.inner.domain_sep(label)
+    }
+
+    fn append_point(&mut self, label: &str, point: &Point) {
+        self.entries.push(TranscriptEntry::Point {
+            label: label.to_string(),
+            point: point.clone(),
+        });
+        self.inner.append_point(label, point)
+    }
+
+    fn append_scalar(&mut self, label: &str, scalar: &ScalarField) {
+        self.entries.push(TranscriptEntry::Scalar {
+            label: label.to_string(),
+            scalar: scalar.clone(),
+        });
+        self.inner.append_scalar(label, scalar)
+    }
+
+    fn challenge_scalar(&mut self, label: &str) -> ScalarField {
+        let challenge = self.inner.challenge_scalar(label);
+        self.entries.push(TranscriptEntry::Challenge {
+            label: label.to_string(),
+            challenge: challenge.clone(),
+        });
+        challenge
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        proof::{lagrange_basis::LagrangeBasis, MultiProof, ProverMultiQuery, VerifierMultiQuery},
+        utils::array_long,
+    };
+
+    use super::*;
+
+    #[test]
+    fn same_challenges() {
+        let mut transcript = Transcript::new("simple_protocol");
+        let mut recording = RecordingTranscript::new("simple_protocol");
+        for t in [
+            &mut transcript as &mut dyn TranscriptProtocol,
+            &mut recording,
+        ] {
+            t.append_scalar("five", &ScalarField::from(5u64));
+            t.append_point("generator", &Point::prime_subgroup_generator());
+        }
+        assert_eq!(
+            recording.challenge_scalar("simple_challenge"),
+            transcript.challenge_scalar("simple_challenge")
+        );
+        assert_eq!(recording.entries().len(), 4);
+    }
+
+    #[test]
+    fn diff_proofs() {
+        let f = |x: u8| ScalarField::from(x) + ScalarField::one();
+        let poly = LagrangeBasis::new(array_long(f));
+        let commitment = poly.commit();
+
+        let mut prover_queries = ProverMultiQuery::new();
+        prover_queries.add_vector(commitment.clone(), poly, [1, 2]);
+        let mut prover_transcript = RecordingTranscript::new("test");
+        let proof = MultiProof::create(prover_queries, &mut prover_transcript).unwrap();
+
+        let mut verifier_queries = VerifierMultiQuery::new();
+        verifier_queries.add_for_commitment(&commitment, [(1, f(1)), (2, f(2))]);
+        let mut verifier_transcript = RecordingTranscript::new("test");
+        proof
+            .verify(verifier_queries, &mut verifier_transcript)
+            .unwrap();
+        assert_eq!(prover_transcript.diff(&verifier_transcript), None);
+
+        // The claimed value of the second opening is wrong
+        let mut verifier_queries = VerifierMultiQuery::new();
+        verifier_queries.add_for_commitment(&commitment, [(1, f(1)), (2, f(3))]);
+        let mut verifier_transcript = RecordingTranscript::new("test");
+        assert!(proof
+            .verify(verifier_queries, &mut verifier_transcript)
+            .is_err());
+        let divergence = prover_transcript.diff(&verifier_transcript).unwrap();
+        // label, domain separator, 3 entries for the first query, 2 entries for the second query
+        assert_eq!(divergence.index, 7);
+        assert_eq!(
+            divergence.left,
+            Some(TranscriptEntry::Scalar {
+                label: "y".to_string(),
+                scalar: f(2)
+            })
+        );
+        assert_eq!(
+            divergence.right,
+            Some(TranscriptEntry::Scalar {
+                label: "y".to_string(),
+                scalar: f(3)
+            })
+        );
+    }
+
+    #[test]
+    fn diff_different_length() {
+        let mut left = RecordingTranscript::new("test");
+        let right = RecordingTranscript::new("test");
+        left.domain_sep("extra");
+        assert_eq!(
+            left.diff(&right),
+            Some(TranscriptDivergence {
+                index: 1,
+                left: Some(TranscriptEntry::DomainSep("extra".to_string())),
+                right: None,
+            })
+        );
+    }
+}
@@ -3,12 +3,25 @@ use sha2::{Digest, Sha256};
 
 use crate::{Point, ScalarField};
 
+/// The Fiat-Shamir transcript, used to derive challenges from the messages of the protocol.
+pub trait TranscriptProtocol {
+    fn domain_sep(&mut self, label: &str);
+
+    fn append_point(&mut self, label: &str, point: &Point);
+
+    fn append_scalar(&mut self, label: &str, scalar: &ScalarField);
+
+    fn challenge_scalar(&mut self, label: &str) -> ScalarField;
+}
+
+/// The SHA-256 based transcript, compatible with other Verkle trie implementations.
 pub struct Transcript {
     hasher: Sha256,
 }
 
 impl Transcript {
     pub const PORTAL_NETWORK_LABEL: &'static str = "portal-network";
+    pub const VERKLE_TRIE_LABEL: &'static str = "vt";
 
     pub fn new(label: &str) -> Self {
         Self {
@@ -45,6 +58,24 @@ impl Transcript {
     }
 }
 
+impl TranscriptProtocol for Transcript {
+    fn domain_sep(&mut self, label: &str) {
+        Transcript::domain_sep(self, label)
+    }
+
+    fn append_point(&mut self, label: &str, point: &Point) {
+        Transcript::append_point(self, label, point)
+    }
+
+    fn append_scalar(&mut self, label: &str, scalar: &ScalarField) {
+        Transcript::append_scalar(self, label, scalar)
+    }
+
+    fn challenge_scalar(&mut self, label: &str) -> ScalarField {
+        Transcript::challenge_scalar(self, label)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // Based on https://github.com/crate-crypto/verkle-trie-ref/blob/2332ab82a77c17024861eb47fd140301c27de980/ipa/transcript_test.py
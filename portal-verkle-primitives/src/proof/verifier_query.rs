@@ -1,7 +1,9 @@
+use alloy_primitives::B256;
 use derive_more::{Deref, DerefMut, IntoIterator};
 
 use crate::{Point, ScalarField};
 
+#[derive(Clone)]
 pub struct VerifierQuery {
     /// The commitment of the polynomial
     pub commitment: Point,
@@ -11,7 +13,7 @@ pub struct VerifierQuery {
     pub y: ScalarField,
 }
 
-#[derive(Default, Deref, DerefMut, IntoIterator)]
+#[derive(Clone, Default, Deref, DerefMut, IntoIterator)]
 pub struct VerifierMultiQuery(Vec<VerifierQuery>);
 
 impl VerifierMultiQuery {
@@ -53,6 +55,16 @@ impl VerifierMultiQuery {
                 }),
         );
     }
+
+    /// Removes duplicate queries and sorts the remaining ones canonically (by commitment, opening
+    /// index and value).
+    ///
+    /// Queries for the same commitment and opening index but different value are not removed, as
+    /// at most one of them can be proven.
+    pub fn deduplicate(&mut self) {
+        self.sort_by_cached_key(|query| (B256::from(&query.commitment), query.z, query.y.clone()));
+        self.dedup_by(|a, b| a.commitment == b.commitment && a.z == b.z && a.y == b.y);
+    }
 }
 
 impl FromIterator<VerifierQuery> for VerifierMultiQuery {
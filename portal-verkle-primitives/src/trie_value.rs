@@ -22,6 +22,12 @@ impl From<U256> for TrieValue {
     }
 }
 
+impl From<&TrieValue> for U256 {
+    fn from(value: &TrieValue) -> Self {
+        U256::from_le_bytes(value.0 .0)
+    }
+}
+
 pub trait TrieValueSplit {
     /// Splits self into low (first 16 bytes) and high (second 16 bytes) values, and converts them
     /// to `Fr` scalar field.
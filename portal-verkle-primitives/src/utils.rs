@@ -1,7 +1,58 @@
-use std::array;
+use std::{
+    any::{Any, TypeId},
+    array,
+    collections::HashMap,
+    sync::RwLock,
+};
+
+use once_cell::sync::Lazy;
 
 use crate::constants::{PORTAL_NETWORK_NODE_WIDTH, VERKLE_NODE_WIDTH};
 
+/// The size of the domain (`N`) that polynomials are evaluated on.
+///
+/// Valid domain sizes are powers of two that are not bigger than [VERKLE_NODE_WIDTH] (so that
+/// every point in the domain fits in `u8`).
+pub(crate) struct DomainSize<const N: usize>;
+
+impl<const N: usize> DomainSize<N> {
+    /// Fails compilation if used with invalid domain size.
+    pub const CHECK: () = assert!(
+        N.is_power_of_two() && N <= VERKLE_NODE_WIDTH,
+        "Domain size should be power of two, not bigger than 256"
+    );
+
+    /// The `log2(N)`, e.g. number of IPA rounds.
+    pub const BITS: usize = N.trailing_zeros() as usize;
+}
+
+/// Returns the instance of the type that is shared by the whole program, initializing it on the
+/// first call.
+///
+/// This is a replacement for generic statics, which Rust doesn't support. The `init` shouldn't
+/// call this function.
+pub(crate) fn generic_static<T: Any + Send + Sync>(init: impl FnOnce() -> T) -> &'static T {
+    static INSTANCES: Lazy<RwLock<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>> =
+        Lazy::new(Default::default);
+
+    let type_id = TypeId::of::<T>();
+    let instance = INSTANCES
+        .read()
+        .expect("generic statics lock shouldn't be poisoned")
+        .get(&type_id)
+        .copied();
+    let instance = instance.unwrap_or_else(|| {
+        *INSTANCES
+            .write()
+            .expect("generic statics lock shouldn't be poisoned")
+            .entry(type_id)
+            .or_insert_with(|| Box::leak(Box::new(init())))
+    });
+    instance
+        .downcast_ref()
+        .expect("generic static should have correct type")
+}
+
 /// Creates the array that has [VERKLE_NODE_WIDTH] length.
 pub fn array_long<T>(f: impl Fn(u8) -> T) -> [T; VERKLE_NODE_WIDTH] {
     array::from_fn(|index| f(index as u8))
@@ -87,3 +138,42 @@ pub(crate) mod leaf_utils {
             .flat_map(suffix_openings)
     }
 }
+
+/// Helpers that use multiple threads (via rayon) if the `parallel` feature is enabled, and a
+/// single thread otherwise. The order of the results is always deterministic.
+pub(crate) mod parallel_utils {
+    #[cfg(feature = "parallel")]
+    use rayon::prelude::*;
+
+    use crate::constants::PORTAL_NETWORK_NODE_WIDTH;
+
+    /// Maps all items, preserving their order.
+    pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+        #[cfg(feature = "parallel")]
+        return items.par_iter().map(f).collect();
+        #[cfg(not(feature = "parallel"))]
+        return items.iter().map(f).collect();
+    }
+
+    /// Runs both closures and returns their results.
+    pub fn join<A: Send, B: Send>(
+        a: impl FnOnce() -> A + Send,
+        b: impl FnOnce() -> B + Send,
+    ) -> (A, B) {
+        #[cfg(feature = "parallel")]
+        return rayon::join(a, b);
+        #[cfg(not(feature = "parallel"))]
+        return (a(), b());
+    }
+
+    /// Same as [array_short](super::array_short).
+    pub fn array_short<T: Send>(
+        f: impl Fn(u8) -> T + Sync + Send,
+    ) -> [T; PORTAL_NETWORK_NODE_WIDTH] {
+        let indices = super::array_short(|index| index);
+        match map(&indices, |index| f(*index)).try_into() {
+            Ok(result) => result,
+            Err(_) => unreachable!("result should have the same length as indices"),
+        }
+    }
+}
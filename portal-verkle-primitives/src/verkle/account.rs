@@ -0,0 +1,194 @@
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+
+use crate::TrieValue;
+
+use super::{
+    error::VerkleTrieError,
+    storage::{AccountStorageLayout, CODE_CHUNK_SIZE},
+    store::NodeStore,
+    VerkleTrie,
+};
+
+/// The account header, as stored in the trie.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Account {
+    pub version: U256,
+    pub balance: U256,
+    pub nonce: U256,
+    pub code_hash: B256,
+    pub code_size: U256,
+}
+
+impl<S: NodeStore> VerkleTrie<S> {
+    /// Returns the account with the given address, or `None` if it doesn't exist.
+    ///
+    /// The account exists if any of its version, balance, nonce or code hash is present in the
+    /// trie. Missing values are zero, except for the code hash, which is the hash of empty code.
+    pub fn account(&self, address: Address) -> Result<Option<Account>, VerkleTrieError> {
+        let storage_layout = AccountStorageLayout::new(address);
+        let version = self.get(&storage_layout.version_key())?;
+        let balance = self.get(&storage_layout.balance_key())?;
+        let nonce = self.get(&storage_layout.nonce_key())?;
+        let code_hash = self.get(&storage_layout.code_hash_key())?;
+        if version.is_none() && balance.is_none() && nonce.is_none() && code_hash.is_none() {
+            return Ok(None);
+        }
+        let code_size = self.get(&storage_layout.code_size_key())?;
+
+        let to_u256 = |value: Option<&TrieValue>| value.map_or(U256::ZERO, Into::into);
+        Ok(Some(Account {
+            version: to_u256(version),
+            balance: to_u256(balance),
+            nonce: to_u256(nonce),
+            code_hash: code_hash.map_or_else(|| keccak256([]), |code_hash| code_hash.0),
+            code_size: to_u256(code_size),
+        }))
+    }
+
+    /// Returns the value of the account's storage slot, or `None` if it isn't set.
+    pub fn storage(
+        &self,
+        address: Address,
+        storage_key: U256,
+    ) -> Result<Option<TrieValue>, VerkleTrieError> {
+        let storage_layout = AccountStorageLayout::new(address);
+        Ok(self
+            .get(&storage_layout.storage_slot_key(storage_key))?
+            .copied())
+    }
+
+    /// Returns the bytecode of the account, or `None` if account doesn't exist.
+    ///
+    /// The bytecode is reassembled from the code chunks, based on the code size of the account, and
+    /// validated with [AccountStorageLayout::dechunkify_code].
+    pub fn code(&self, address: Address) -> Result<Option<Bytes>, VerkleTrieError> {
+        let Some(account) = self.account(address)? else {
+            return Ok(None);
+        };
+        let code_size =
+            usize::try_from(account.code_size).map_err(|_| VerkleTrieError::InvalidCodeSize {
+                address,
+                code_size: account.code_size,
+            })?;
+
+        let storage_layout = AccountStorageLayout::new(address);
+        let chunks = (0..code_size.div_ceil(CODE_CHUNK_SIZE))
+            .map(|chunk_id| {
+                self.get(&storage_layout.code_key(chunk_id))?
+                    .copied()
+                    .ok_or(VerkleTrieError::MissingCodeChunk { address, chunk_id })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let code = AccountStorageLayout::dechunkify_code(code_size, &chunks)
+            .map_err(|source| VerkleTrieError::InvalidCode { address, source })?;
+        Ok(Some(code.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use alloy_primitives::address;
+
+    use crate::verkle::{error::CodeChunksError, genesis_config::GenesisConfig};
+
+    use super::*;
+
+    #[test]
+    fn devnet6_genesis() -> anyhow::Result<()> {
+        let reader = BufReader::new(File::open("../testdata/genesis.json")?);
+        let genesis_config: GenesisConfig = serde_json::from_reader(reader)?;
+        let mut trie = VerkleTrie::new();
+        trie.update(&genesis_config.clone().into_state_writes())?;
+
+        for (address, account_alloc) in genesis_config.alloc {
+            let code = account_alloc.code.unwrap_or_default();
+            assert_eq!(
+                trie.account(address)?,
+                Some(Account {
+                    version: U256::ZERO,
+                    balance: account_alloc.balance,
+                    nonce: account_alloc.nonce.unwrap_or_default(),
+                    code_hash: keccak256(&code),
+                    code_size: U256::from(code.len()),
+                })
+            );
+            assert_eq!(trie.code(address)?, Some(code));
+            for (storage_key, value) in account_alloc.storage.unwrap_or_default() {
+                assert_eq!(trie.storage(address, storage_key)?, Some(value));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn missing_account() -> anyhow::Result<()> {
+        let mut trie = VerkleTrie::new();
+        let address = address!("fffffffffffffffffffffffffffffffffffffffe");
+        assert_eq!(trie.account(address)?, None);
+        assert_eq!(trie.code(address)?, None);
+        assert_eq!(trie.storage(address, U256::ZERO)?, None);
+
+        // Only balance is set
+        let storage_layout = AccountStorageLayout::new(address);
+        trie.insert(&storage_layout.balance_key(), U256::from(5).into())?;
+        assert_eq!(
+            trie.account(address)?,
+            Some(Account {
+                version: U256::ZERO,
+                balance: U256::from(5),
+                nonce: U256::ZERO,
+                code_hash: keccak256([]),
+                code_size: U256::ZERO,
+            })
+        );
+        assert_eq!(trie.code(address)?, Some(Bytes::new()));
+        Ok(())
+    }
+
+    #[test]
+    fn missing_code_chunk() -> anyhow::Result<()> {
+        let mut trie = VerkleTrie::new();
+        let address = address!("fffffffffffffffffffffffffffffffffffffffe");
+        let storage_layout = AccountStorageLayout::new(address);
+        trie.insert(&storage_layout.version_key(), U256::ZERO.into())?;
+        trie.insert(&storage_layout.code_size_key(), U256::from(40).into())?;
+        let (key, value) = storage_layout.chunkify_code(&[0; 40])[0];
+        trie.insert(&key, value)?;
+
+        assert!(matches!(
+            trie.code(address),
+            Err(VerkleTrieError::MissingCodeChunk { chunk_id: 1, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_code_chunk() -> anyhow::Result<()> {
+        let mut trie = VerkleTrie::new();
+        let address = address!("fffffffffffffffffffffffffffffffffffffffe");
+        let storage_layout = AccountStorageLayout::new(address);
+        trie.insert(&storage_layout.version_key(), U256::ZERO.into())?;
+        trie.insert(&storage_layout.code_size_key(), U256::from(40).into())?;
+        // PUSH32 at the end of the first chunk, but second chunk has no push data
+        let mut code = [0; 40];
+        code[30] = 0x7f;
+        let chunks = storage_layout.chunkify_code(&[0; 40]);
+        trie.insert(&chunks[0].0, storage_layout.chunkify_code(&code)[0].1)?;
+        trie.insert(&chunks[1].0, chunks[1].1)?;
+
+        assert!(matches!(
+            trie.code(address),
+            Err(VerkleTrieError::InvalidCode {
+                source: CodeChunksError::InvalidPushDataOffset {
+                    chunk_id: 1,
+                    expected: 31,
+                    actual: 0,
+                },
+                ..
+            })
+        ));
+        Ok(())
+    }
+}
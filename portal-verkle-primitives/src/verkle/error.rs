@@ -1,6 +1,7 @@
+use alloy_primitives::{Address, Bytes, B256, U256};
 use thiserror::Error;
 
-use crate::Stem;
+use crate::{proof::ProofError, EcDecodeError, Stem, TrieKey};
 
 #[derive(Debug, Error)]
 pub enum VerkleTrieError {
@@ -8,4 +9,74 @@ pub enum VerkleTrieError {
     UnexpectedStem { expected: Stem, actual: Stem },
     #[error("Node not found at depth {depth} for stem {stem} during the trie traversal")]
     NodeNotFound { stem: Stem, depth: usize },
+    #[error("Node at depth {depth} for stem {stem} is not known")]
+    UnknownNode { stem: Stem, depth: usize },
+    #[error("Node at path {path} with commitment {commitment} is missing from the store")]
+    MissingStoredNode { path: Bytes, commitment: B256 },
+    #[error("Root {root} is not a valid commitment: {source}")]
+    InvalidRoot {
+        root: B256,
+        #[source]
+        source: EcDecodeError,
+    },
+    #[error("Code size {code_size} of account {address} is too big")]
+    InvalidCodeSize { address: Address, code_size: U256 },
+    #[error("Code chunk {chunk_id} of account {address} is missing")]
+    MissingCodeChunk { address: Address, chunk_id: usize },
+    #[error("Code of account {address} is invalid: {source}")]
+    InvalidCode {
+        address: Address,
+        #[source]
+        source: CodeChunksError,
+    },
+    #[error(transparent)]
+    NodeStore(#[from] NodeStoreError),
+    #[error("Proof can't be created without keys")]
+    NoProofKeys,
+    #[error("Failed to create proof: {0}")]
+    Proof(#[from] ProofError),
+}
+
+#[derive(Debug, Error)]
+pub enum PartialVerkleTrieError {
+    #[error("Node at depth {depth} for stem {stem} is not known")]
+    UnknownNode { stem: Stem, depth: usize },
+    #[error("Value for key {0} is not known")]
+    UnknownValue(TrieKey),
+    #[error(transparent)]
+    Trie(#[from] VerkleTrieError),
+}
+
+#[derive(Debug, Error)]
+pub enum CodeChunksError {
+    #[error("Expected {expected} code chunks for code size {code_size}, but received {actual}")]
+    InvalidChunkCount {
+        code_size: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Code chunk {chunk_id} should start with {expected} push data bytes, but is {actual}")]
+    InvalidPushDataOffset {
+        chunk_id: usize,
+        expected: u8,
+        actual: u8,
+    },
+    #[error("Code chunk {chunk_id} is not zero padded")]
+    NonZeroPadding { chunk_id: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum NodeStoreError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to decode stored node: {0:?}")]
+    Decode(ssz::DecodeError),
+    #[error("Path is longer than stem")]
+    InvalidPath,
+}
+
+impl From<ssz::DecodeError> for NodeStoreError {
+    fn from(err: ssz::DecodeError) -> Self {
+        Self::Decode(err)
+    }
 }
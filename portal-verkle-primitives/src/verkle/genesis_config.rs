@@ -1,11 +1,11 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::HashMap;
 
-use alloy_primitives::{b256, keccak256, Address, Bytes, B256, U256};
+use alloy_primitives::{b256, Address, Bytes, B256, U256};
 use serde::{Deserialize, Serialize};
 
-use crate::{Stem, TrieKey, TrieValue};
+use crate::TrieValue;
 
-use super::{storage::AccountStorageLayout, StateWrites, StemStateWrite};
+use super::{StateWrites, StateWritesBuilder};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -28,50 +28,23 @@ impl GenesisConfig {
         b256!("1fbf85345a3cbba9a6d44f991b721e55620a22397c2a93ee8d5011136ac300ee");
 
     pub fn into_state_writes(self) -> StateWrites {
-        let mut state_writes = BTreeMap::<Stem, StemStateWrite>::new();
-        let mut insert_state_write = |key: TrieKey, value: TrieValue| {
-            let stem = key.stem();
-            state_writes
-                .entry(stem)
-                .or_insert_with(|| StemStateWrite {
-                    stem,
-                    writes: HashMap::new(),
-                })
-                .writes
-                .insert(key.suffix(), value);
-        };
-
+        let mut builder = StateWritesBuilder::new();
         for (address, account_alloc) in self.alloc {
-            let storage_layout = AccountStorageLayout::new(address);
-            insert_state_write(storage_layout.version_key(), U256::ZERO.into());
-            insert_state_write(storage_layout.balance_key(), account_alloc.balance.into());
-            insert_state_write(
-                storage_layout.nonce_key(),
-                account_alloc.nonce.unwrap_or(U256::ZERO).into(),
+            builder.create_account(
+                address,
+                account_alloc.balance,
+                account_alloc.nonce.unwrap_or(U256::ZERO),
             );
-
-            match &account_alloc.code {
-                None => insert_state_write(storage_layout.code_hash_key(), keccak256([]).into()),
-                Some(code) => {
-                    insert_state_write(storage_layout.code_hash_key(), keccak256(code).into());
-                    insert_state_write(
-                        storage_layout.code_size_key(),
-                        U256::from(code.len()).into(),
-                    );
-                    for (key, value) in storage_layout.chunkify_code(code) {
-                        insert_state_write(key, value);
-                    }
-                }
+            if let Some(code) = &account_alloc.code {
+                builder.set_code(address, code);
             }
-
             if let Some(storage) = account_alloc.storage {
                 for (storage_key, value) in storage {
-                    insert_state_write(storage_layout.storage_slot_key(storage_key), value);
+                    builder.set_storage(address, storage_key, value);
                 }
             }
         }
-
-        StateWrites::new(state_writes.into_values().collect())
+        builder.build()
     }
 }
 
@@ -4,15 +4,27 @@ use derive_more::{Constructor, Deref, Index};
 
 use crate::{ssz::TriePath, Stem, TrieValue};
 
+pub use account::Account;
 use nodes::{branch::BranchNode, leaf::LeafNode};
+pub use partial_trie::PartialVerkleTrie;
+pub use portal_content::create_portal_nodes;
+pub use snapshot::VerkleTrieSnapshot;
+pub use state_writes_builder::StateWritesBuilder;
 pub use trie::VerkleTrie;
 
+mod account;
 pub mod error;
 pub mod genesis_config;
 pub mod nodes;
+mod partial_trie;
+mod portal_content;
+mod snapshot;
+mod state_writes_builder;
 pub mod storage;
+pub mod store;
 mod trie;
 pub mod trie_printer;
+mod witness_builder;
 
 #[derive(Debug, Clone, PartialEq, Eq, Constructor, Deref, Index)]
 pub struct StateWrites(Vec<StemStateWrite>);
@@ -1,20 +1,29 @@
-use std::mem;
+use std::{mem, sync::Arc};
 
 use crate::{
     constants::VERKLE_NODE_WIDTH,
     proof::lagrange_basis::LagrangeBasis,
-    ssz::TriePath,
+    ssz::{SparseVector, TriePath},
     utils::array_long,
-    verkle::{NewBranchNode, StemStateWrite},
-    ScalarField, Stem, TrieKey, TrieValue,
+    verkle::{
+        error::{NodeStoreError, VerkleTrieError},
+        store::{NodeStore, StoredBranchNode, StoredNode},
+        NewBranchNode, StemStateWrite,
+    },
+    Point, ScalarField, Stem, TrieKey, TrieValue,
 };
 
-use super::{commitment::Commitment, leaf::LeafNode, Node};
+use super::{commitment::Commitment, leaf::LeafNode, Node, UnloadedNode};
 
+#[derive(Clone)]
 pub struct BranchNode {
     depth: usize,
     commitment: Commitment,
     children: [Node; VERKLE_NODE_WIDTH],
+    /// Children that are not known, if this is partially known node (see [Self::new_partial]).
+    ///
+    /// Unknown children are stored as [Node::Empty], but they can't be read nor updated.
+    unknown_children: Option<Box<[bool; VERKLE_NODE_WIDTH]>>,
 }
 
 impl BranchNode {
@@ -26,6 +35,82 @@ impl BranchNode {
             depth,
             commitment: Commitment::zero(),
             children: array_long(|_| Node::Empty),
+            unknown_children: None,
+        }
+    }
+
+    /// Creates the branch node whose children are not known, apart from the provided ones.
+    pub(crate) fn new_partial(
+        depth: usize,
+        commitment: Point,
+        known_children: impl IntoIterator<Item = (u8, Node)>,
+    ) -> Self {
+        let mut children = array_long(|_| Node::Empty);
+        let mut unknown_children = Box::new([true; VERKLE_NODE_WIDTH]);
+        for (index, child) in known_children {
+            children[index as usize] = child;
+            unknown_children[index as usize] = false;
+        }
+        Self {
+            depth,
+            commitment: Commitment::new(commitment),
+            children,
+            unknown_children: Some(unknown_children),
+        }
+    }
+
+    /// Creates the branch node from its stored form, with all children unloaded.
+    pub(crate) fn from_stored(depth: usize, stored_node: &StoredBranchNode) -> Self {
+        Self {
+            depth,
+            commitment: Commitment::new(stored_node.commitment.clone()),
+            children: array_long(|index| match &stored_node.children[index as usize] {
+                None => Node::Empty,
+                Some(commitment) => Node::Unloaded(Arc::new(UnloadedNode::new(Commitment::new(
+                    commitment.clone(),
+                )))),
+            }),
+            unknown_children: None,
+        }
+    }
+
+    pub(crate) fn to_stored(&self) -> StoredBranchNode {
+        StoredBranchNode {
+            commitment: self.commitment.to_point(),
+            children: SparseVector::new(self.children.each_ref().map(|child| {
+                if child.is_empty() {
+                    None
+                } else {
+                    Some(child.commitment().to_point())
+                }
+            })),
+        }
+    }
+
+    /// Saves this node and all of its descendants that are loaded into memory.
+    ///
+    /// Children that were loaded but not modified since are not saved again.
+    pub(crate) fn save(&self, path: &[u8], store: &impl NodeStore) -> Result<(), NodeStoreError> {
+        for (index, child) in self.children.iter().enumerate() {
+            let child_path = [path, &[index as u8]].concat();
+            match child {
+                Node::Empty | Node::Unloaded(_) => {}
+                Node::Branch(branch_node) => branch_node.save(&child_path, store)?,
+                Node::Leaf(leaf_node) => store.save(
+                    &child_path,
+                    StoredNode::Leaf(Arc::new(leaf_node.to_stored())),
+                )?,
+            }
+        }
+        store.save(path, StoredNode::Branch(Arc::new(self.to_stored())))
+    }
+
+    /// Unloads all children from memory. They should be [saved](Self::save) first.
+    pub(crate) fn unload_children(&mut self) {
+        for child in self.children.iter_mut() {
+            if !child.is_empty() {
+                *child = Node::Unloaded(Arc::new(UnloadedNode::new(child.commitment().clone())));
+            }
         }
     }
 
@@ -37,21 +122,47 @@ impl BranchNode {
         &self.commitment
     }
 
-    pub fn get(&self, key: &TrieKey) -> Option<&TrieValue> {
-        let index = key[self.depth] as usize;
-        match &self.children[index] {
-            Node::Empty => None,
-            Node::Branch(branch_node) => branch_node.get(key),
+    /// Returns the value of the key, loading nodes from the store if needed.
+    ///
+    /// Returns error if the node on the path to the key is not known or can't be loaded.
+    pub fn get(
+        &self,
+        key: &TrieKey,
+        store: &impl NodeStore,
+    ) -> Result<Option<&TrieValue>, VerkleTrieError> {
+        let index = key[self.depth];
+        if !self.is_child_known(index) {
+            return Err(VerkleTrieError::UnknownNode {
+                stem: key.stem(),
+                depth: self.depth + 1,
+            });
+        }
+        let mut child = &self.children[index as usize];
+        if let Node::Unloaded(unloaded_node) = child {
+            child = unloaded_node.load(&key[..=self.depth], store)?;
+        }
+        match child {
+            Node::Empty => Ok(None),
+            Node::Branch(branch_node) => branch_node.get(key, store),
             Node::Leaf(leaf_node) => {
                 if key.starts_with_stem(leaf_node.stem()) {
-                    leaf_node.get(key.suffix())
+                    Ok(leaf_node.get(key.suffix()))
                 } else {
-                    None
+                    Ok(None)
                 }
             }
+            Node::Unloaded(_) => unreachable!("Loaded node can't be unloaded"),
         }
     }
 
+    /// Returns whether the child is known. Only partially known nodes have unknown children.
+    pub(crate) fn is_child_known(&self, index: u8) -> bool {
+        !self
+            .unknown_children
+            .as_ref()
+            .is_some_and(|unknown_children| unknown_children[index as usize])
+    }
+
     pub(crate) fn get_child(&self, index: u8) -> &Node {
         &self.children[index as usize]
     }
@@ -65,59 +176,75 @@ impl BranchNode {
 
     /// Returns by how much the commitmant hash has changed and the path to the new branch node if
     /// one was created.
-    pub fn update(&mut self, state_write: &StemStateWrite) -> (ScalarField, NewBranchNode) {
+    ///
+    /// Nodes on the path to the stem are loaded from the store if needed. Returns error if the
+    /// update would modify the child that is not known or that can't be loaded.
+    pub fn update(
+        &mut self,
+        state_write: &StemStateWrite,
+        store: &impl NodeStore,
+    ) -> Result<(ScalarField, NewBranchNode), VerkleTrieError> {
         if state_write.writes.is_empty() {
-            return (ScalarField::zero(), None);
+            return Ok((ScalarField::zero(), None));
         }
 
         let index = state_write.stem[self.depth];
+        if !self.is_child_known(index) {
+            return Err(VerkleTrieError::UnknownNode {
+                stem: state_write.stem,
+                depth: self.depth + 1,
+            });
+        }
         let child = &mut self.children[index as usize];
+        child.make_loaded(&state_write.stem[..=self.depth], store)?;
         match child {
             Node::Empty => {
-                let mut leaf_node = Box::new(LeafNode::new(state_write.stem));
+                let mut leaf_node = LeafNode::new(state_write.stem);
                 leaf_node.update(&state_write.writes);
-                *child = Node::Leaf(leaf_node);
-                (
+                *child = Node::Leaf(Arc::new(leaf_node));
+                Ok((
                     self.commitment
                         .update_single(index, child.commitment().as_scalar()),
                     None,
-                )
+                ))
             }
             Node::Branch(branch_node) => {
-                let (child_value_diff, new_branch_node) = branch_node.update(state_write);
-                (
+                let (child_value_diff, new_branch_node) =
+                    Arc::make_mut(branch_node).update(state_write, store)?;
+                Ok((
                     self.commitment.update_single(index, &child_value_diff),
                     new_branch_node,
-                )
+                ))
             }
             Node::Leaf(leaf_node) => {
                 if leaf_node.stem() == &state_write.stem {
-                    let child_value_diff = leaf_node.update(&state_write.writes);
-                    (
+                    let child_value_diff = Arc::make_mut(leaf_node).update(&state_write.writes);
+                    Ok((
                         self.commitment.update_single(index, &child_value_diff),
                         None,
-                    )
+                    ))
                 } else {
                     let old_child_value = leaf_node.commitment().to_scalar();
 
                     let old_child_index_in_new_branch = leaf_node.stem()[self.depth + 1];
                     let old_child = mem::replace(child, Node::Empty);
 
-                    let mut branch_node = Box::new(Self::new(self.depth + 1));
+                    let mut branch_node = Self::new(self.depth + 1);
                     branch_node.set_child(old_child_index_in_new_branch, old_child);
-                    branch_node.update(state_write);
+                    branch_node.update(state_write, store)?;
 
                     let new_branch_node = Some(TriePath::from(
                         state_write.stem[..branch_node.depth].to_vec(),
                     ));
-                    *child = Node::Branch(branch_node);
+                    *child = Node::Branch(Arc::new(branch_node));
                     let child_value_diff = child.commitment().as_scalar() - old_child_value;
-                    (
+                    Ok((
                         self.commitment.update_single(index, &child_value_diff),
                         new_branch_node,
-                    )
+                    ))
                 }
             }
+            Node::Unloaded(_) => unreachable!("Node was loaded above"),
         }
     }
 
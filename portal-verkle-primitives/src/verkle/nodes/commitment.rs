@@ -1,5 +1,7 @@
 use std::{ops::AddAssign, sync::OnceLock};
 
+use itertools::{zip_eq, Itertools};
+
 use crate::{Point, ScalarField, CRS};
 
 #[derive(Clone)]
@@ -33,6 +35,25 @@ impl Commitment {
         self.as_scalar().clone()
     }
 
+    /// Computes scalars (see [as_scalar](Self::as_scalar)) of all commitments at once.
+    ///
+    /// This is significantly faster than computing them one by one, as it requires a single field
+    /// inversion.
+    pub fn batch_init_scalars<'a>(commitments: impl IntoIterator<Item = &'a Self>) {
+        let commitments = commitments
+            .into_iter()
+            .filter(|commitment| commitment.scalar.get().is_none())
+            .collect_vec();
+        let points = commitments
+            .iter()
+            .map(|commitment| commitment.to_point())
+            .collect_vec();
+        for (commitment, scalar) in zip_eq(commitments, Point::batch_map_to_scalar_field(&points)) {
+            // Ignore error as it can only happen if scalar was set in the meantime
+            let _ = commitment.scalar.set(scalar);
+        }
+    }
+
     /// Updates this commitment and returns by how much the commitment hash changed.
     ///
     /// @param diff By how much scalar changed.
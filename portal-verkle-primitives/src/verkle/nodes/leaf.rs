@@ -7,11 +7,13 @@ use crate::{
     proof::lagrange_basis::LagrangeBasis,
     ssz::SparseVector,
     utils::array_long_const,
-    ScalarField, Stem, TrieValue, TrieValueSplit, CRS,
+    verkle::store::StoredLeafNode,
+    Point, ScalarField, Stem, TrieValue, TrieValueSplit, CRS,
 };
 
 use super::commitment::Commitment;
 
+#[derive(Clone)]
 pub struct LeafNode {
     marker: u64,
     stem: Stem,
@@ -40,6 +42,52 @@ impl LeafNode {
         }
     }
 
+    /// Creates the leaf node that is only partially known.
+    ///
+    /// Only provided values are set. Suffix commitments that are not known are set to zero, and
+    /// they shouldn't be updated.
+    pub(crate) fn new_partial(
+        stem: Stem,
+        commitment: Point,
+        c1: Option<Point>,
+        c2: Option<Point>,
+        values: impl IntoIterator<Item = (u8, TrieValue)>,
+    ) -> Self {
+        let mut leaf_node = Self {
+            marker: 1,
+            stem,
+            commitment: Commitment::new(commitment),
+            c1: c1.map_or_else(Commitment::zero, Commitment::new),
+            c2: c2.map_or_else(Commitment::zero, Commitment::new),
+            values: SparseVector::default(),
+        };
+        for (index, value) in values {
+            leaf_node.values[index as usize] = Some(value);
+        }
+        leaf_node
+    }
+
+    pub(crate) fn from_stored(stored_node: StoredLeafNode) -> Self {
+        Self {
+            marker: 1,
+            stem: stored_node.stem,
+            commitment: Commitment::new(stored_node.commitment),
+            c1: Commitment::new(stored_node.c1),
+            c2: Commitment::new(stored_node.c2),
+            values: stored_node.values,
+        }
+    }
+
+    pub(crate) fn to_stored(&self) -> StoredLeafNode {
+        StoredLeafNode {
+            stem: self.stem,
+            commitment: self.commitment.to_point(),
+            c1: self.c1.to_point(),
+            c2: self.c2.to_point(),
+            values: self.values.clone(),
+        }
+    }
+
     pub fn marker(&self) -> u64 {
         self.marker
     }
@@ -1,7 +1,16 @@
+use std::sync::Arc;
+
+use alloy_primitives::Bytes;
 use branch::BranchNode;
 use commitment::Commitment;
 use leaf::LeafNode;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
+
+use super::{
+    error::VerkleTrieError,
+    store::{NodeStore, StoredNode},
+};
+use crate::Point;
 
 pub mod branch;
 pub mod commitment;
@@ -9,10 +18,17 @@ pub mod leaf;
 pub mod portal_branch_node_builder;
 pub mod portal_leaf_node_builder;
 
+/// The node of the trie.
+///
+/// Branch and leaf nodes are shared between the trie and its snapshots, and they are copied only
+/// when modified (copy-on-write).
+#[derive(Clone)]
 pub enum Node {
     Empty,
-    Branch(Box<BranchNode>),
-    Leaf(Box<LeafNode>),
+    Branch(Arc<BranchNode>),
+    Leaf(Arc<LeafNode>),
+    /// The node that is persisted in the [NodeStore](crate::verkle::store::NodeStore).
+    Unloaded(Arc<UnloadedNode>),
 }
 pub static ZERO: Lazy<Commitment> = Lazy::new(Commitment::zero);
 
@@ -22,10 +38,84 @@ impl Node {
             Node::Empty => &ZERO,
             Node::Branch(branch_node) => branch_node.commitment(),
             Node::Leaf(leaf_node) => leaf_node.commitment(),
+            Node::Unloaded(unloaded_node) => unloaded_node.commitment(),
         }
     }
 
     pub fn is_empty(&self) -> bool {
         matches!(self, Node::Empty)
     }
+
+    /// Loads the node with the given path and commitment from the store.
+    pub(crate) fn load(
+        store: &impl NodeStore,
+        path: &[u8],
+        commitment: &Point,
+    ) -> Result<Self, VerkleTrieError> {
+        match store.load(path, commitment)? {
+            Some(StoredNode::Branch(stored_node)) => Ok(Node::Branch(Arc::new(
+                BranchNode::from_stored(path.len(), &stored_node),
+            ))),
+            Some(StoredNode::Leaf(stored_node)) => Ok(Node::Leaf(Arc::new(LeafNode::from_stored(
+                Arc::unwrap_or_clone(stored_node),
+            )))),
+            None => Err(VerkleTrieError::MissingStoredNode {
+                path: Bytes::copy_from_slice(path),
+                commitment: commitment.into(),
+            }),
+        }
+    }
+
+    /// Replaces the unloaded node with the node itself, loading it from the store if needed.
+    pub(crate) fn make_loaded(
+        &mut self,
+        path: &[u8],
+        store: &impl NodeStore,
+    ) -> Result<(), VerkleTrieError> {
+        if let Node::Unloaded(unloaded_node) = self {
+            *self = unloaded_node.load(path, store)?.clone();
+        }
+        Ok(())
+    }
+}
+
+/// The node that is persisted in the store, and that is loaded into memory only when needed.
+///
+/// Once loaded, the node is cached until it's modified or unloaded. The cache is shared with all
+/// snapshots that contain this node.
+pub struct UnloadedNode {
+    commitment: Commitment,
+    node: OnceCell<Node>,
+}
+
+impl UnloadedNode {
+    pub fn new(commitment: Commitment) -> Self {
+        Self {
+            commitment,
+            node: OnceCell::new(),
+        }
+    }
+
+    pub fn commitment(&self) -> &Commitment {
+        &self.commitment
+    }
+
+    /// Returns the node, if it's loaded.
+    pub fn get(&self) -> Option<&Node> {
+        self.node.get()
+    }
+
+    /// Returns the node, loading it first if needed.
+    pub fn get_or_load<E>(&self, load: impl FnOnce() -> Result<Node, E>) -> Result<&Node, E> {
+        self.node.get_or_try_init(load)
+    }
+
+    /// Returns the node, loading it from the store first if needed.
+    pub(crate) fn load(
+        &self,
+        path: &[u8],
+        store: &impl NodeStore,
+    ) -> Result<&Node, VerkleTrieError> {
+        self.get_or_load(|| Node::load(store, path, self.commitment.as_point()))
+    }
 }
@@ -7,9 +7,9 @@ use crate::{
         BranchBundleNode, BranchBundleNodeWithProof, BranchFragmentNode,
         BranchFragmentNodeWithProof,
     },
-    proof::{lagrange_basis::LagrangeBasis, BundleProof, MultiProof, ProverMultiQuery},
+    proof::{lagrange_basis::LagrangeBasis, BundleProof, MultiProof, ProofError, ProverMultiQuery},
     ssz::{SparseVector, TriePathWithCommitments},
-    utils::{array_long, array_short, branch_utils},
+    utils::{array_long, array_short, branch_utils, parallel_utils},
     Point, ScalarField, CRS,
 };
 
@@ -76,7 +76,12 @@ impl<'a> PortalBranchNodeBuilder<'a> {
             ));
         }
 
-        let fragments = array_short(|fragment_index| {
+        Commitment::batch_init_scalars(
+            (0..=u8::MAX)
+                .map(|child_index| node.get_child(child_index).commitment())
+                .filter(|commitment| !commitment.is_zero()),
+        );
+        let fragments = parallel_utils::array_short(|fragment_index| {
             let fragment_children = array_short(|fragment_child_index| {
                 let child_index = branch_utils::child_index(fragment_index, fragment_child_index);
                 let commitment = node.get_child(child_index).commitment();
@@ -111,7 +116,7 @@ impl<'a> PortalBranchNodeBuilder<'a> {
         self.branch_node.commitment().as_point()
     }
 
-    pub fn bundle_node(&self) -> BranchBundleNode {
+    pub fn bundle_node(&self) -> Result<BranchBundleNode, ProofError> {
         let fragment_commitments = self.fragments.each_ref().map(|fragment| {
             if fragment.is_zero() {
                 None
@@ -132,19 +137,26 @@ impl<'a> PortalBranchNodeBuilder<'a> {
             );
         }
 
-        BranchBundleNode::new(
+        Ok(BranchBundleNode::new(
             SparseVector::new(fragment_commitments),
-            BundleProof::new(MultiProof::create_portal_network_proof(bundle_multiquery)),
-        )
+            BundleProof::new(MultiProof::create_portal_network_proof(bundle_multiquery)?),
+        ))
     }
 
-    pub fn bundle_node_with_proof(&self, block_hash: B256) -> BranchBundleNodeWithProof {
-        BranchBundleNodeWithProof {
-            node: self.bundle_node(),
+    pub fn bundle_node_with_proof(
+        &self,
+        block_hash: B256,
+    ) -> Result<BranchBundleNodeWithProof, ProofError> {
+        let (node, multiproof) = parallel_utils::join(
+            || self.bundle_node(),
+            || MultiProof::create_portal_network_proof(self.trie_path_multiquery.clone()),
+        );
+        Ok(BranchBundleNodeWithProof {
+            node: node?,
             block_hash,
             trie_path: self.trie_path.clone(),
-            multiproof: MultiProof::create_portal_network_proof(self.trie_path_multiquery.clone()),
-        }
+            multiproof: multiproof?,
+        })
     }
 
     pub fn fragment_commitment(&self, fragment_index: u8) -> &Point {
@@ -164,7 +176,7 @@ impl<'a> PortalBranchNodeBuilder<'a> {
         &self,
         fragment_index: u8,
         block_hash: B256,
-    ) -> BranchFragmentNodeWithProof {
+    ) -> Result<BranchFragmentNodeWithProof, ProofError> {
         let bundle_commitment = self.branch_node.commitment().to_point();
 
         let mut multiquery = self.trie_path_multiquery.clone();
@@ -174,12 +186,28 @@ impl<'a> PortalBranchNodeBuilder<'a> {
             branch_utils::openings(fragment_index),
         );
 
-        BranchFragmentNodeWithProof {
+        Ok(BranchFragmentNodeWithProof {
             node: self.fragment_node(fragment_index),
             block_hash,
             bundle_commitment,
             trie_path: self.trie_path.clone(),
-            multiproof: MultiProof::create_portal_network_proof(multiquery),
-        }
+            multiproof: MultiProof::create_portal_network_proof(multiquery)?,
+        })
+    }
+
+    /// Same as [fragment_node_with_proof](Self::fragment_node_with_proof), but for many fragments.
+    ///
+    /// Fragments are created concurrently if `parallel` feature is enabled, but they are returned
+    /// in the same order as `fragment_indices`.
+    pub fn fragment_nodes_with_proof(
+        &self,
+        fragment_indices: &[u8],
+        block_hash: B256,
+    ) -> Result<Vec<BranchFragmentNodeWithProof>, ProofError> {
+        parallel_utils::map(fragment_indices, |fragment_index| {
+            self.fragment_node_with_proof(*fragment_index, block_hash)
+        })
+        .into_iter()
+        .collect()
     }
 }
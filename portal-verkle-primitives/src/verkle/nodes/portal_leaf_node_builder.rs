@@ -8,9 +8,9 @@ use crate::{
     portal::{
         LeafBundleNode, LeafBundleNodeWithProof, LeafFragmentNode, LeafFragmentNodeWithProof,
     },
-    proof::{lagrange_basis::LagrangeBasis, BundleProof, MultiProof, ProverMultiQuery},
+    proof::{lagrange_basis::LagrangeBasis, BundleProof, MultiProof, ProofError, ProverMultiQuery},
     ssz::{SparseVector, TriePathCommitments},
-    utils::{array_long_const, array_short, leaf_utils},
+    utils::{array_long_const, array_short, leaf_utils, parallel_utils},
     verkle::PathToLeaf,
     Point, ScalarField, Stem, TrieValue, CRS,
 };
@@ -80,7 +80,7 @@ impl<'a> PortalLeafNodeBuilder<'a> {
             leaf,
         } = path_to_leaf;
 
-        let fragments = array_short(|fragment_index| {
+        let fragments = parallel_utils::array_short(|fragment_index| {
             let fragment_values = array_short(|fragment_child_index| {
                 let child_index =
                     fragment_child_index + fragment_index * PORTAL_NETWORK_NODE_WIDTH as u8;
@@ -125,7 +125,7 @@ impl<'a> PortalLeafNodeBuilder<'a> {
         self.leaf_node.commitment().as_point()
     }
 
-    pub fn bundle_node(&self) -> LeafBundleNode {
+    pub fn bundle_node(&self) -> Result<LeafBundleNode, ProofError> {
         let fragments = self.fragments.each_ref().map(|fragment| {
             if fragment.is_zero() {
                 None
@@ -146,21 +146,28 @@ impl<'a> PortalLeafNodeBuilder<'a> {
             );
         }
 
-        LeafBundleNode::new(
+        Ok(LeafBundleNode::new(
             self.leaf_node.marker(),
             *self.leaf_node.stem(),
             SparseVector::new(fragments),
-            BundleProof::new(MultiProof::create_portal_network_proof(bundle_multiquery)),
-        )
+            BundleProof::new(MultiProof::create_portal_network_proof(bundle_multiquery)?),
+        ))
     }
 
-    pub fn bundle_node_with_proof(&self, block_hash: B256) -> LeafBundleNodeWithProof {
-        LeafBundleNodeWithProof {
-            node: self.bundle_node(),
+    pub fn bundle_node_with_proof(
+        &self,
+        block_hash: B256,
+    ) -> Result<LeafBundleNodeWithProof, ProofError> {
+        let (node, multiproof) = parallel_utils::join(
+            || self.bundle_node(),
+            || MultiProof::create_portal_network_proof(self.trie_path_multiquery.clone()),
+        );
+        Ok(LeafBundleNodeWithProof {
+            node: node?,
             block_hash,
             trie_path: self.trie_path.clone(),
-            multiproof: MultiProof::create_portal_network_proof(self.trie_path_multiquery.clone()),
-        }
+            multiproof: multiproof?,
+        })
     }
 
     pub fn fragment_commitment(&self, fragment_index: u8) -> &Point {
@@ -179,7 +186,7 @@ impl<'a> PortalLeafNodeBuilder<'a> {
         &self,
         fragment_index: u8,
         block_hash: B256,
-    ) -> LeafFragmentNodeWithProof {
+    ) -> Result<LeafFragmentNodeWithProof, ProofError> {
         let fragment = &self.fragments[fragment_index as usize];
 
         let mut multiquery = self.trie_path_multiquery.clone();
@@ -205,14 +212,30 @@ impl<'a> PortalLeafNodeBuilder<'a> {
             leaf_utils::suffix_openings(fragment.fragment_index),
         );
 
-        LeafFragmentNodeWithProof {
+        Ok(LeafFragmentNodeWithProof {
             node: self.fragment_node(fragment_index),
             block_hash,
             marker: self.leaf_node.marker(),
             bundle_commitment: self.leaf_node.commitment().to_point(),
             suffix_commitment: fragment.suffix_commitment.to_point(),
             trie_path: self.trie_path.clone(),
-            multiproof: MultiProof::create_portal_network_proof(multiquery),
-        }
+            multiproof: MultiProof::create_portal_network_proof(multiquery)?,
+        })
+    }
+
+    /// Same as [fragment_node_with_proof](Self::fragment_node_with_proof), but for many fragments.
+    ///
+    /// Fragments are created concurrently if `parallel` feature is enabled, but they are returned
+    /// in the same order as `fragment_indices`.
+    pub fn fragment_nodes_with_proof(
+        &self,
+        fragment_indices: &[u8],
+        block_hash: B256,
+    ) -> Result<Vec<LeafFragmentNodeWithProof>, ProofError> {
+        parallel_utils::map(fragment_indices, |fragment_index| {
+            self.fragment_node_with_proof(*fragment_index, block_hash)
+        })
+        .into_iter()
+        .collect()
     }
 }
@@ -0,0 +1,418 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use alloy_primitives::B256;
+
+use super::{
+    error::PartialVerkleTrieError,
+    nodes::{branch::BranchNode, leaf::LeafNode, Node},
+    store::MemoryNodeStore,
+    StemStateWrite,
+};
+use crate::{
+    ssz::TriePath,
+    verkle::StateWrites,
+    witness::{ExecutionWitness, ReconstructedLeaf, ReconstructedTrie, WitnessVerificationError},
+    Point, Stem, TrieKey, TrieValue,
+};
+
+/// The Verkle Trie that is only partially known.
+///
+/// Branch nodes keep track of their children that are not known, which can't be read nor updated.
+/// The trie can be updated as long as updates don't touch unknown parts of the trie, which is the
+/// case when updating with the state writes of the block whose witness was used to create this
+/// trie.
+pub struct PartialVerkleTrie {
+    root_node: BranchNode,
+    /// The known suffixes of leaf nodes that are only partially known.
+    ///
+    /// Leaf nodes that are not present here are fully known (e.g. created by update).
+    partial_leaves: HashMap<Stem, HashSet<u8>>,
+    /// The store used for updating nodes. It stays empty, as partial trie has no unloaded nodes.
+    store: MemoryNodeStore,
+}
+
+impl PartialVerkleTrie {
+    /// Creates the partial trie from the execution witness.
+    ///
+    /// The witness is verified against the provided pre-state root first.
+    pub fn from_witness(
+        witness: &ExecutionWitness,
+        pre_state_root: &B256,
+    ) -> Result<Self, WitnessVerificationError> {
+        witness.verify(pre_state_root)?;
+
+        let mut values = HashMap::<Stem, Vec<(u8, Option<TrieValue>)>>::new();
+        for (key, value) in witness.pre_state()? {
+            values
+                .entry(key.stem())
+                .or_default()
+                .push((key.suffix(), value));
+        }
+
+        let pre_state_root = Point::try_from(pre_state_root)
+            .map_err(WitnessVerificationError::InvalidPreStateRoot)?;
+        let reconstructed_trie = ReconstructedTrie::new(witness, pre_state_root)?;
+        let mut partial_leaves = HashMap::new();
+        let root_node = Self::create_branch(
+            &reconstructed_trie,
+            &[],
+            reconstructed_trie.root.clone(),
+            &values,
+            &mut partial_leaves,
+        );
+        Ok(Self {
+            root_node,
+            partial_leaves,
+            store: MemoryNodeStore::new(),
+        })
+    }
+
+    fn create_branch(
+        reconstructed_trie: &ReconstructedTrie,
+        path: &[u8],
+        commitment: Point,
+        values: &HashMap<Stem, Vec<(u8, Option<TrieValue>)>>,
+        partial_leaves: &mut HashMap<Stem, HashSet<u8>>,
+    ) -> BranchNode {
+        let children_paths = reconstructed_trie
+            .branches
+            .range(path.to_vec()..)
+            .map(|(child_path, _)| child_path)
+            .take_while(|child_path| child_path.starts_with(path))
+            .chain(
+                reconstructed_trie
+                    .leaves
+                    .range(path.to_vec()..)
+                    .map(|(child_path, _)| child_path)
+                    .take_while(|child_path| child_path.starts_with(path)),
+            )
+            .chain(
+                reconstructed_trie
+                    .empty
+                    .range(path.to_vec()..)
+                    .take_while(|child_path| child_path.starts_with(path)),
+            )
+            .filter(|child_path| child_path.len() == path.len() + 1)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut children = vec![];
+        for child_path in children_paths {
+            let child_index = child_path[path.len()];
+            let child = if let Some(commitment) = reconstructed_trie.branches.get(&child_path) {
+                Node::Branch(Arc::new(Self::create_branch(
+                    reconstructed_trie,
+                    &child_path,
+                    commitment.clone(),
+                    values,
+                    partial_leaves,
+                )))
+            } else if let Some(leaf) = reconstructed_trie.leaves.get(&child_path) {
+                Node::Leaf(Arc::new(Self::create_leaf(leaf, values, partial_leaves)))
+            } else {
+                Node::Empty
+            };
+            children.push((child_index, child));
+        }
+        BranchNode::new_partial(path.len(), commitment, children)
+    }
+
+    fn create_leaf(
+        leaf: &ReconstructedLeaf,
+        values: &HashMap<Stem, Vec<(u8, Option<TrieValue>)>>,
+        partial_leaves: &mut HashMap<Stem, HashSet<u8>>,
+    ) -> LeafNode {
+        let values = values
+            .get(&leaf.stem)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        partial_leaves.insert(
+            leaf.stem,
+            values.iter().map(|(suffix, _)| *suffix).collect(),
+        );
+        LeafNode::new_partial(
+            leaf.stem,
+            leaf.commitment.clone(),
+            leaf.c1.clone(),
+            leaf.c2.clone(),
+            values
+                .iter()
+                .filter_map(|(suffix, value)| value.map(|value| (*suffix, value))),
+        )
+    }
+
+    pub fn root_commitment(&self) -> &Point {
+        self.root_node.commitment().as_point()
+    }
+
+    pub fn root(&self) -> B256 {
+        self.root_commitment().into()
+    }
+
+    /// Returns the value of the key, or error if it's not known.
+    pub fn get(&self, key: &TrieKey) -> Result<Option<&TrieValue>, PartialVerkleTrieError> {
+        let stem = key.stem();
+        let Some(leaf) = self.find_leaf(&stem)? else {
+            return Ok(None);
+        };
+        match self.partial_leaves.get(&stem) {
+            Some(known_suffixes) if !known_suffixes.contains(&key.suffix()) => {
+                Err(PartialVerkleTrieError::UnknownValue(*key))
+            }
+            _ => Ok(leaf.get(key.suffix())),
+        }
+    }
+
+    /// Updates the trie in the same way as [VerkleTrie::update](super::VerkleTrie::update).
+    ///
+    /// Returns error if any of the written keys is not known, in which case the trie is not
+    /// modified.
+    pub fn update(
+        &mut self,
+        state_writes: &StateWrites,
+    ) -> Result<HashSet<TriePath>, PartialVerkleTrieError> {
+        for stem_state_write in state_writes.iter() {
+            self.check_update(stem_state_write)?;
+        }
+
+        let mut created_branches = HashSet::new();
+        for stem_state_write in state_writes.iter() {
+            if stem_state_write.writes.is_empty() {
+                continue;
+            }
+            let (_, created_branch) = self.root_node.update(stem_state_write, &self.store)?;
+            if let Some(created_branch) = created_branch {
+                created_branches.insert(created_branch);
+            }
+            if let Some(known_suffixes) = self.partial_leaves.get_mut(&stem_state_write.stem) {
+                known_suffixes.extend(stem_state_write.writes.keys());
+            }
+        }
+        Ok(created_branches)
+    }
+
+    fn check_update(
+        &self,
+        stem_state_write: &StemStateWrite,
+    ) -> Result<(), PartialVerkleTrieError> {
+        if stem_state_write.writes.is_empty() {
+            return Ok(());
+        }
+        let stem = &stem_state_write.stem;
+        if self.find_leaf(stem)?.is_none() {
+            return Ok(());
+        }
+        if let Some(known_suffixes) = self.partial_leaves.get(stem) {
+            if let Some(suffix) = stem_state_write
+                .writes
+                .keys()
+                .find(|suffix| !known_suffixes.contains(suffix))
+            {
+                return Err(PartialVerkleTrieError::UnknownValue(
+                    TrieKey::from_stem_and_suffix(stem, *suffix),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the leaf node with the given stem, or `None` if stem is not present in the trie.
+    fn find_leaf(&self, stem: &Stem) -> Result<Option<&LeafNode>, PartialVerkleTrieError> {
+        let mut node = &self.root_node;
+        loop {
+            let depth = node.depth();
+            if !node.is_child_known(stem[depth]) {
+                return Err(PartialVerkleTrieError::UnknownNode {
+                    stem: *stem,
+                    depth: depth + 1,
+                });
+            }
+            node = match node.get_child(stem[depth]) {
+                Node::Empty => return Ok(None),
+                Node::Branch(branch_node) => branch_node,
+                Node::Leaf(leaf_node) => {
+                    return Ok((leaf_node.stem() == stem).then_some(leaf_node.as_ref()))
+                }
+                Node::Unloaded(_) => {
+                    return Err(PartialVerkleTrieError::UnknownNode {
+                        stem: *stem,
+                        depth: depth + 1,
+                    })
+                }
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use alloy_primitives::{address, b256, U256};
+
+    use crate::verkle::{
+        error::VerkleTrieError, genesis_config::GenesisConfig, storage::AccountStorageLayout,
+        VerkleTrie,
+    };
+
+    use super::*;
+
+    fn read_genesis() -> GenesisConfig {
+        let reader = BufReader::new(File::open("../testdata/genesis.json").unwrap());
+        serde_json::from_reader(reader).unwrap()
+    }
+
+    #[test]
+    fn devnet6_block1() -> anyhow::Result<()> {
+        let reader = BufReader::new(File::open("../testdata/devnet6_block1_witness.json")?);
+        let witness: ExecutionWitness = serde_json::from_reader(reader)?;
+        let block1_state_root =
+            b256!("5a65582e323fb83ed40438a0c33fa6ebfbc7f45e4c29d112b0142cfeb63f82af");
+
+        let mut trie =
+            PartialVerkleTrie::from_witness(&witness, &GenesisConfig::DEVNET6_STATE_ROOT)?;
+        assert_eq!(trie.root(), GenesisConfig::DEVNET6_STATE_ROOT);
+
+        let new_branch_nodes = trie.update(&witness.state_writes())?;
+        assert_eq!(
+            new_branch_nodes,
+            [TriePath::new(vec![0x5b]).unwrap()].into()
+        );
+        assert_eq!(trie.root(), block1_state_root);
+        Ok(())
+    }
+
+    #[test]
+    fn same_as_full_trie() -> anyhow::Result<()> {
+        let genesis_config = read_genesis();
+        let mut full_trie = VerkleTrie::new();
+        full_trie.update(&genesis_config.clone().into_state_writes())?;
+
+        let mut accessed_keys = vec![];
+        let mut state_writes = vec![];
+        for (index, address) in genesis_config.alloc.keys().take(5).enumerate() {
+            let storage_layout = AccountStorageLayout::new(*address);
+            accessed_keys.push(storage_layout.balance_key());
+            accessed_keys.push(storage_layout.code_size_key());
+            state_writes.push(StemStateWrite {
+                stem: *storage_layout.account_storage_stem(),
+                writes: HashMap::from([
+                    (
+                        storage_layout.nonce_key().suffix(),
+                        TrieValue::from(U256::from(index + 1)),
+                    ),
+                    // Suffix that is not present
+                    (200, TrieValue::from(U256::from(index + 2))),
+                ]),
+            });
+        }
+        let new_account =
+            AccountStorageLayout::new(address!("1000000000000000000000000000000000000000"));
+        state_writes.push(StemStateWrite {
+            stem: *new_account.account_storage_stem(),
+            writes: HashMap::from([(
+                new_account.balance_key().suffix(),
+                TrieValue::from(U256::from(1000)),
+            )]),
+        });
+        let state_writes = StateWrites::new(state_writes);
+
+        let witness = full_trie.create_execution_witness(accessed_keys.clone(), &state_writes)?;
+        let mut partial_trie = PartialVerkleTrie::from_witness(&witness, &full_trie.root())?;
+
+        for key in &accessed_keys {
+            assert_eq!(partial_trie.get(key)?, full_trie.get(key)?);
+        }
+        assert_eq!(partial_trie.get(&new_account.balance_key())?, None);
+
+        assert_eq!(
+            partial_trie.update(&state_writes)?,
+            full_trie.update(&state_writes)?
+        );
+        assert_eq!(partial_trie.root(), full_trie.root());
+        for key in accessed_keys.iter().chain([&new_account.balance_key()]) {
+            assert_eq!(partial_trie.get(key)?, full_trie.get(key)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_keys() -> anyhow::Result<()> {
+        let genesis_config = read_genesis();
+        let mut full_trie = VerkleTrie::new();
+        full_trie.update(&genesis_config.clone().into_state_writes())?;
+
+        let mut addresses = genesis_config.alloc.keys();
+        let accessed_account = AccountStorageLayout::new(*addresses.next().unwrap());
+        let other_account = AccountStorageLayout::new(*addresses.next().unwrap());
+
+        let witness = full_trie.create_execution_witness(
+            [accessed_account.balance_key()],
+            &StateWrites::new(vec![]),
+        )?;
+        let mut partial_trie = PartialVerkleTrie::from_witness(&witness, &full_trie.root())?;
+
+        assert!(matches!(
+            partial_trie.get(&accessed_account.nonce_key()),
+            Err(PartialVerkleTrieError::UnknownValue(_))
+        ));
+        assert!(matches!(
+            partial_trie.get(&other_account.balance_key()),
+            Err(PartialVerkleTrieError::UnknownNode { .. })
+        ));
+
+        let root = partial_trie.root();
+        let state_writes = StateWrites::new(vec![StemStateWrite {
+            stem: *accessed_account.account_storage_stem(),
+            writes: HashMap::from([
+                (accessed_account.balance_key().suffix(), TrieValue::ZERO),
+                (accessed_account.nonce_key().suffix(), TrieValue::ZERO),
+            ]),
+        }]);
+        assert!(matches!(
+            partial_trie.update(&state_writes),
+            Err(PartialVerkleTrieError::UnknownValue(key)) if key == accessed_account.nonce_key()
+        ));
+        assert_eq!(partial_trie.root(), root);
+        Ok(())
+    }
+
+    #[test]
+    fn update_unknown_node() -> anyhow::Result<()> {
+        let genesis_config = read_genesis();
+        let mut full_trie = VerkleTrie::new();
+        full_trie.update(&genesis_config.clone().into_state_writes())?;
+
+        let mut addresses = genesis_config.alloc.keys();
+        let accessed_account = AccountStorageLayout::new(*addresses.next().unwrap());
+        let other_account = AccountStorageLayout::new(*addresses.next().unwrap());
+
+        let witness = full_trie.create_execution_witness(
+            [accessed_account.balance_key()],
+            &StateWrites::new(vec![]),
+        )?;
+        let mut partial_trie = PartialVerkleTrie::from_witness(&witness, &full_trie.root())?;
+
+        let root = partial_trie.root();
+        let state_write = StemStateWrite {
+            stem: *other_account.account_storage_stem(),
+            writes: HashMap::from([(other_account.balance_key().suffix(), TrieValue::ZERO)]),
+        };
+        assert!(matches!(
+            partial_trie.update(&StateWrites::new(vec![state_write.clone()])),
+            Err(PartialVerkleTrieError::UnknownNode { .. })
+        ));
+        // Updating the node directly also fails, instead of treating unknown node as empty
+        assert!(matches!(
+            partial_trie
+                .root_node
+                .update(&state_write, &partial_trie.store),
+            Err(VerkleTrieError::UnknownNode { depth: 1, .. })
+        ));
+        assert_eq!(partial_trie.root(), root);
+        Ok(())
+    }
+}
@@ -0,0 +1,482 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    iter,
+};
+
+use alloy_primitives::B256;
+use itertools::Itertools;
+
+use crate::{
+    constants::PORTAL_NETWORK_NODE_WIDTH,
+    portal::PortalVerkleNodeWithProof,
+    proof::ProofError,
+    ssz::TriePath,
+    utils::{branch_utils, parallel_utils},
+    Stem,
+};
+
+use super::{
+    error::VerkleTrieError,
+    nodes::{
+        portal_branch_node_builder::PortalBranchNodeBuilder,
+        portal_leaf_node_builder::PortalLeafNodeBuilder, Node,
+    },
+    store::NodeStore,
+    PathToLeaf, StateWrites, VerkleTrie,
+};
+
+impl<S: NodeStore> VerkleTrie<S> {
+    /// Updates the trie with the state writes of the block, and returns all Portal Network nodes
+    /// that were changed by it.
+    ///
+    /// See [create_portal_content](Self::create_portal_content) for details.
+    pub fn update_with_portal_content(
+        &mut self,
+        state_writes: &StateWrites,
+        block_hash: B256,
+    ) -> Result<Vec<PortalVerkleNodeWithProof>, VerkleTrieError> {
+        let new_branches = self.update(state_writes)?;
+        self.create_portal_content(state_writes, &new_branches, block_hash)
+    }
+
+    /// Returns all Portal Network nodes that were changed by the block, assuming that the trie
+    /// was already updated with its state writes.
+    ///
+    /// The `new_branches` should be the ones returned by [update](Self::update). Returned nodes
+    /// are:
+    /// - bundle nodes of all branches on the paths to the written stems
+    /// - fragment nodes of these branches that contain the modified child (or all non-empty
+    ///   fragment nodes, if the branch is new)
+    /// - bundle nodes of leaves with the written stems
+    /// - fragment nodes of these leaves that contain written values
+    /// - bundle nodes and all non-empty fragment nodes of leaves that were moved deeper into the
+    ///   trie by a new branch, together with the fragment nodes on their new path
+    ///
+    /// Branch nodes are returned first, ordered by their path, followed by leaf nodes, ordered by
+    /// their stem. Bundle node always precedes its fragment nodes.
+    pub fn create_portal_content(
+        &self,
+        state_writes: &StateWrites,
+        new_branches: &HashSet<TriePath>,
+        block_hash: B256,
+    ) -> Result<Vec<PortalVerkleNodeWithProof>, VerkleTrieError> {
+        // Modified fragments of branch nodes (and any stem that goes through them), by path
+        let mut branch_fragments = BTreeMap::<Vec<u8>, (Stem, BTreeSet<u8>)>::new();
+        // Modified fragments of leaf nodes, by stem
+        let mut leaf_fragments = BTreeMap::<Stem, BTreeSet<u8>>::new();
+        let mut add_path_fragments = |stem: Stem| -> Result<(), VerkleTrieError> {
+            let path_to_leaf = self.traverse_to_leaf(&stem)?;
+            for (depth, (_, child_index)) in path_to_leaf.trie_path.iter().enumerate() {
+                branch_fragments
+                    .entry(stem[..depth].to_vec())
+                    .or_insert_with(|| (stem, BTreeSet::new()))
+                    .1
+                    .insert(branch_utils::fragment_index(*child_index));
+            }
+            Ok(())
+        };
+        for stem_state_write in state_writes.iter() {
+            if stem_state_write.writes.is_empty() {
+                continue;
+            }
+            let stem = stem_state_write.stem;
+            add_path_fragments(stem)?;
+            leaf_fragments.entry(stem).or_default().extend(
+                stem_state_write
+                    .writes
+                    .keys()
+                    .map(|suffix| suffix / PORTAL_NETWORK_NODE_WIDTH as u8),
+            );
+        }
+        // Leaves that were pushed deeper by the new branches have new path, so all of their
+        // nodes have to be created again
+        for new_branch in new_branches {
+            for stem in self.leaf_stems(new_branch)? {
+                if leaf_fragments.contains_key(&stem) {
+                    continue;
+                }
+                add_path_fragments(stem)?;
+                leaf_fragments.insert(stem, (0..PORTAL_NETWORK_NODE_WIDTH as u8).collect());
+            }
+        }
+
+        let mut jobs = vec![];
+        for (path, (stem, fragment_indices)) in branch_fragments {
+            let fragment_indices = if new_branches.contains(&TriePath::from(path.clone())) {
+                (0..PORTAL_NETWORK_NODE_WIDTH as u8).collect()
+            } else {
+                fragment_indices
+            };
+            jobs.push(PortalNodesJob::Branch {
+                path_to_leaf: self.traverse_to_leaf(&stem)?,
+                depth: path.len(),
+                fragment_indices,
+            });
+        }
+        for (stem, fragment_indices) in leaf_fragments {
+            jobs.push(PortalNodesJob::Leaf {
+                path_to_leaf: self.traverse_to_leaf(&stem)?,
+                fragment_indices,
+            });
+        }
+        Ok(create_nodes(&jobs, block_hash)?)
+    }
+
+    /// Returns the stems of all leaves in the subtree of the branch node at the given path.
+    fn leaf_stems(&self, path: &[u8]) -> Result<Vec<Stem>, VerkleTrieError> {
+        let mut branch_node = self.root_node();
+        for depth in 0..path.len() {
+            match self.child(branch_node, &path[..=depth])? {
+                Node::Branch(child) => branch_node = child,
+                _ => return Ok(vec![]),
+            }
+        }
+
+        let mut stems = vec![];
+        let mut branches = vec![(branch_node, path.to_vec())];
+        while let Some((branch_node, path)) = branches.pop() {
+            for child_index in 0..=u8::MAX {
+                let child_path = [path.as_slice(), &[child_index]].concat();
+                match self.child(branch_node, &child_path)? {
+                    Node::Branch(child) => branches.push((child, child_path)),
+                    Node::Leaf(leaf) => stems.push(*leaf.stem()),
+                    Node::Empty | Node::Unloaded(_) => {}
+                }
+            }
+        }
+        Ok(stems)
+    }
+}
+
+/// Creates all Portal Network nodes of the leaves and of the branches on their paths.
+///
+/// For every leaf and every branch on its path, the bundle node and all non-empty fragment
+/// nodes are created. Branches that are shared between paths are created only once.
+///
+/// Nodes are ordered in the same way as by [VerkleTrie::create_portal_content]. If `parallel`
+/// feature is enabled, nodes are created concurrently (but their order doesn't change).
+pub fn create_portal_nodes(
+    paths: &[PathToLeaf],
+    block_hash: B256,
+) -> Result<Vec<PortalVerkleNodeWithProof>, ProofError> {
+    let all_fragments = || (0..PORTAL_NETWORK_NODE_WIDTH as u8).collect::<BTreeSet<_>>();
+
+    let mut branches = BTreeMap::new();
+    let mut leaves = BTreeMap::new();
+    for path_to_leaf in paths {
+        let stem = path_to_leaf.leaf.stem();
+        for depth in 0..path_to_leaf.trie_path.len() {
+            branches
+                .entry(stem[..depth].to_vec())
+                .or_insert_with(|| PortalNodesJob::Branch {
+                    path_to_leaf: path_to_leaf.clone(),
+                    depth,
+                    fragment_indices: all_fragments(),
+                });
+        }
+        leaves.insert(
+            *stem,
+            PortalNodesJob::Leaf {
+                path_to_leaf: path_to_leaf.clone(),
+                fragment_indices: all_fragments(),
+            },
+        );
+    }
+
+    let jobs = branches
+        .into_values()
+        .chain(leaves.into_values())
+        .collect_vec();
+    create_nodes(&jobs, block_hash)
+}
+
+/// The Portal Network nodes that should be created for a single branch or leaf node.
+enum PortalNodesJob<'a> {
+    /// The branch node at the given depth of the path.
+    Branch {
+        path_to_leaf: PathToLeaf<'a>,
+        depth: usize,
+        fragment_indices: BTreeSet<u8>,
+    },
+    Leaf {
+        path_to_leaf: PathToLeaf<'a>,
+        fragment_indices: BTreeSet<u8>,
+    },
+}
+
+impl PortalNodesJob<'_> {
+    /// Creates the bundle node, followed by the fragment nodes (empty fragments are skipped).
+    fn create(&self, block_hash: B256) -> Result<Vec<PortalVerkleNodeWithProof>, ProofError> {
+        match self {
+            Self::Branch {
+                path_to_leaf,
+                depth,
+                fragment_indices,
+            } => {
+                let builder = PortalBranchNodeBuilder::new(
+                    path_to_leaf.trie_path[*depth].0,
+                    &path_to_leaf.trie_path[..*depth],
+                )
+                .expect("trie path should match the depth of the branch node");
+                let fragment_indices = fragment_indices
+                    .iter()
+                    .copied()
+                    .filter(|fragment_index| {
+                        !builder.fragment_commitment(*fragment_index).is_zero()
+                    })
+                    .collect_vec();
+
+                let (bundle, fragments) = parallel_utils::join(
+                    || builder.bundle_node_with_proof(block_hash),
+                    || builder.fragment_nodes_with_proof(&fragment_indices, block_hash),
+                );
+                Ok(iter::once(PortalVerkleNodeWithProof::BranchBundle(bundle?))
+                    .chain(
+                        fragments?
+                            .into_iter()
+                            .map(PortalVerkleNodeWithProof::BranchFragment),
+                    )
+                    .collect())
+            }
+            Self::Leaf {
+                path_to_leaf,
+                fragment_indices,
+            } => {
+                let builder = PortalLeafNodeBuilder::new(path_to_leaf);
+                let fragment_indices = fragment_indices
+                    .iter()
+                    .copied()
+                    .filter(|fragment_index| {
+                        !builder.fragment_commitment(*fragment_index).is_zero()
+                    })
+                    .collect_vec();
+
+                let (bundle, fragments) = parallel_utils::join(
+                    || builder.bundle_node_with_proof(block_hash),
+                    || builder.fragment_nodes_with_proof(&fragment_indices, block_hash),
+                );
+                Ok(iter::once(PortalVerkleNodeWithProof::LeafBundle(bundle?))
+                    .chain(
+                        fragments?
+                            .into_iter()
+                            .map(PortalVerkleNodeWithProof::LeafFragment),
+                    )
+                    .collect())
+            }
+        }
+    }
+}
+
+/// Creates the nodes of all jobs, preserving their order.
+fn create_nodes(
+    jobs: &[PortalNodesJob],
+    block_hash: B256,
+) -> Result<Vec<PortalVerkleNodeWithProof>, ProofError> {
+    parallel_utils::map(jobs, |job| job.create(block_hash))
+        .into_iter()
+        .flatten_ok()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs::File, io::BufReader};
+
+    use alloy_primitives::b256;
+    use ssz::Encode;
+
+    use crate::{
+        verkle::{genesis_config::GenesisConfig, StemStateWrite},
+        witness::ExecutionWitness,
+        TrieValue,
+    };
+
+    use super::*;
+
+    #[test]
+    fn devnet6_block1() -> anyhow::Result<()> {
+        let reader = BufReader::new(File::open("../testdata/genesis.json")?);
+        let genesis_config: GenesisConfig = serde_json::from_reader(reader)?;
+        let reader = BufReader::new(File::open("../testdata/devnet6_block1_witness.json")?);
+        let witness: ExecutionWitness = serde_json::from_reader(reader)?;
+        let block1_state_root =
+            b256!("5a65582e323fb83ed40438a0c33fa6ebfbc7f45e4c29d112b0142cfeb63f82af");
+        let block1_hash = B256::repeat_byte(1);
+
+        let mut trie = VerkleTrie::new();
+        trie.update(&genesis_config.into_state_writes())?;
+        let state_writes = witness.state_writes();
+        let content = trie.update_with_portal_content(&state_writes, block1_hash)?;
+        assert_eq!(trie.root(), block1_state_root);
+
+        let stems = content
+            .iter()
+            .filter_map(|node| match node {
+                PortalVerkleNodeWithProof::LeafBundle(node) => {
+                    Some((B256::from(node.node.commitment()), *node.node.stem()))
+                }
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+        for stem_state_write in state_writes.iter() {
+            let leaf_commitment = trie
+                .traverse_to_leaf(&stem_state_write.stem)?
+                .leaf
+                .commitment();
+            assert!(stems.contains_key(&B256::from(leaf_commitment.as_point())));
+        }
+
+        for node in &content {
+            assert_eq!(node.block_hash(), block1_hash);
+        }
+        verify_nodes(&content, &block1_state_root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn create_portal_nodes_for_many_paths() -> anyhow::Result<()> {
+        let reader = BufReader::new(File::open("../testdata/genesis.json")?);
+        let genesis_config: GenesisConfig = serde_json::from_reader(reader)?;
+        let state_writes = genesis_config.into_state_writes();
+        let mut trie = VerkleTrie::new();
+        trie.update(&state_writes)?;
+        let block_hash = B256::repeat_byte(1);
+
+        let mut paths = state_writes
+            .iter()
+            .take(3)
+            .map(|stem_state_write| trie.traverse_to_leaf(&stem_state_write.stem))
+            .collect::<Result<Vec<_>, _>>()?;
+        let nodes = create_portal_nodes(&paths, block_hash)?;
+        verify_nodes(&nodes, &trie.root())?;
+
+        // Root node should be first and only once
+        let branch_bundle_paths = nodes
+            .iter()
+            .filter_map(|node| match node {
+                PortalVerkleNodeWithProof::BranchBundle(node) => Some(node.trie_path.len()),
+                _ => None,
+            })
+            .collect_vec();
+        assert_eq!(branch_bundle_paths[0], 0);
+        assert_eq!(
+            branch_bundle_paths.iter().filter(|len| **len == 0).count(),
+            1
+        );
+
+        // Leaf bundles should be ordered by stem
+        let leaf_bundle_stems = nodes
+            .iter()
+            .filter_map(|node| match node {
+                PortalVerkleNodeWithProof::LeafBundle(node) => Some(*node.node.stem()),
+                _ => None,
+            })
+            .collect_vec();
+        let mut stems = paths.iter().map(|path| *path.leaf.stem()).collect_vec();
+        stems.sort();
+        assert_eq!(leaf_bundle_stems, stems);
+
+        // Order of nodes shouldn't depend on the order of paths
+        paths.reverse();
+        assert_eq!(
+            create_portal_nodes(&paths, block_hash)?
+                .iter()
+                .map(|node| node.as_ssz_bytes())
+                .collect_vec(),
+            nodes.iter().map(|node| node.as_ssz_bytes()).collect_vec(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn new_branch_moves_existing_leaf() -> anyhow::Result<()> {
+        let existing_stem = Stem::from_slice(&[[1, 2, 3].as_slice(), &[0; 28]].concat());
+        let new_stem = Stem::from_slice(&[[1, 2, 9].as_slice(), &[0; 28]].concat());
+        let stem_state_write = |stem: Stem| StemStateWrite {
+            stem,
+            writes: HashMap::from([(0, TrieValue::from(B256::repeat_byte(1)))]),
+        };
+        let block_hash = B256::repeat_byte(2);
+
+        let mut trie = VerkleTrie::new();
+        trie.update(&StateWrites::new(vec![stem_state_write(existing_stem)]))?;
+        let content = trie.update_with_portal_content(
+            &StateWrites::new(vec![stem_state_write(new_stem)]),
+            block_hash,
+        )?;
+        verify_nodes(&content, &trie.root())?;
+
+        // Both leaves have their bundle and fragment nodes, with the new path
+        for stem in [existing_stem, new_stem] {
+            let path_to_leaf = trie.traverse_to_leaf(&stem)?;
+            assert_eq!(path_to_leaf.trie_path.len(), 3);
+            let leaf_bundle = content
+                .iter()
+                .find_map(|node| match node {
+                    PortalVerkleNodeWithProof::LeafBundle(node) if node.node.stem() == &stem => {
+                        Some(node)
+                    }
+                    _ => None,
+                })
+                .expect("leaf bundle should be present");
+            assert_eq!(leaf_bundle.trie_path.len(), 3);
+            assert!(content.iter().any(|node| matches!(
+                node,
+                PortalVerkleNodeWithProof::LeafFragment(fragment)
+                    if fragment.bundle_commitment == *leaf_bundle.node.commitment()
+            )));
+        }
+
+        // All branches on the new path have bundle and fragment nodes with both leaves
+        let branch_bundle_paths = content
+            .iter()
+            .filter_map(|node| match node {
+                PortalVerkleNodeWithProof::BranchBundle(node) => {
+                    Some(node.trie_path.iter().map(|(_, index)| *index).collect_vec())
+                }
+                _ => None,
+            })
+            .collect_vec();
+        assert_eq!(branch_bundle_paths, vec![vec![], vec![1], vec![1, 2]]);
+        assert_eq!(
+            content
+                .iter()
+                .filter(|node| matches!(node, PortalVerkleNodeWithProof::BranchFragment(_)))
+                .count(),
+            3
+        );
+        Ok(())
+    }
+
+    fn verify_nodes(nodes: &[PortalVerkleNodeWithProof], state_root: &B256) -> anyhow::Result<()> {
+        let stems = nodes
+            .iter()
+            .filter_map(|node| match node {
+                PortalVerkleNodeWithProof::LeafBundle(node) => {
+                    Some((B256::from(node.node.commitment()), *node.node.stem()))
+                }
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+        for node in nodes {
+            let commitment = node.commitment();
+            match node {
+                PortalVerkleNodeWithProof::BranchBundle(node) => {
+                    node.verify(commitment, state_root)?
+                }
+                PortalVerkleNodeWithProof::BranchFragment(node) => {
+                    node.verify(commitment, state_root)?
+                }
+                PortalVerkleNodeWithProof::LeafBundle(node) => {
+                    node.verify(commitment, state_root)?
+                }
+                PortalVerkleNodeWithProof::LeafFragment(node) => node.verify(
+                    commitment,
+                    state_root,
+                    &stems[&B256::from(&node.bundle_commitment)],
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
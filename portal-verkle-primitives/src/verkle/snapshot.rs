@@ -0,0 +1,157 @@
+use alloy_primitives::B256;
+
+use super::{
+    store::{MemoryNodeStore, NodeStore},
+    VerkleTrie,
+};
+
+/// The read-only snapshot of the [VerkleTrie], created with [VerkleTrie::snapshot].
+///
+/// The snapshot shares unmodified nodes with the trie (and with other snapshots), so creating it
+/// is cheap. It can be read from other threads while the trie keeps being modified, and it can be
+/// used to [rollback](VerkleTrie::rollback) the trie (e.g. in case of reorg).
+pub struct VerkleTrieSnapshot<S = MemoryNodeStore> {
+    trie: VerkleTrie<S>,
+}
+
+impl<S: NodeStore> VerkleTrieSnapshot<S> {
+    pub(super) fn new(trie: VerkleTrie<S>) -> Self {
+        Self { trie }
+    }
+
+    pub fn root(&self) -> B256 {
+        self.trie.root()
+    }
+
+    /// Returns the trie as it was when the snapshot was taken.
+    pub fn trie(&self) -> &VerkleTrie<S> {
+        &self.trie
+    }
+}
+
+impl<S: NodeStore> Clone for VerkleTrieSnapshot<S> {
+    fn clone(&self) -> Self {
+        self.trie.snapshot()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader, thread};
+
+    use alloy_primitives::b256;
+
+    use crate::{
+        verkle::genesis_config::GenesisConfig, witness::ExecutionWitness, TrieKey, TrieValue,
+    };
+
+    use super::*;
+
+    const BLOCK1_STATE_ROOT: B256 =
+        b256!("5a65582e323fb83ed40438a0c33fa6ebfbc7f45e4c29d112b0142cfeb63f82af");
+
+    fn read_genesis() -> anyhow::Result<GenesisConfig> {
+        let reader = BufReader::new(File::open("../testdata/genesis.json")?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    fn read_witness() -> anyhow::Result<ExecutionWitness> {
+        let reader = BufReader::new(File::open("../testdata/devnet6_block1_witness.json")?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Returns pre-state and post-state values of all keys from the witness.
+    fn witness_values(
+        witness: &ExecutionWitness,
+    ) -> Vec<(TrieKey, Option<TrieValue>, Option<TrieValue>)> {
+        witness
+            .state_diff
+            .iter()
+            .flat_map(|stem_state_diff| {
+                stem_state_diff.suffix_diffs.iter().map(|suffix_diff| {
+                    (
+                        TrieKey::from_stem_and_suffix(&stem_state_diff.stem, suffix_diff.suffix),
+                        suffix_diff.current_value,
+                        suffix_diff.new_value.or(suffix_diff.current_value),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rollback() -> anyhow::Result<()> {
+        let witness = read_witness()?;
+
+        let mut trie = VerkleTrie::new();
+        trie.update(&read_genesis()?.into_state_writes())?;
+        let genesis_snapshot = trie.snapshot();
+
+        trie.update(&witness.state_writes())?;
+        assert_eq!(trie.root(), BLOCK1_STATE_ROOT);
+        assert_eq!(genesis_snapshot.root(), GenesisConfig::DEVNET6_STATE_ROOT);
+
+        trie.rollback(&genesis_snapshot);
+        assert_eq!(trie.root(), GenesisConfig::DEVNET6_STATE_ROOT);
+        for (key, pre_value, _) in witness_values(&witness) {
+            assert_eq!(trie.get(&key)?, pre_value.as_ref());
+        }
+
+        // The same block can be applied again after rollback
+        trie.update(&witness.state_writes())?;
+        assert_eq!(trie.root(), BLOCK1_STATE_ROOT);
+        Ok(())
+    }
+
+    #[test]
+    fn rollback_after_commit() -> anyhow::Result<()> {
+        let witness = read_witness()?;
+
+        let mut trie = VerkleTrie::new();
+        trie.update(&read_genesis()?.into_state_writes())?;
+        trie.commit()?;
+        let genesis_snapshot = trie.snapshot();
+
+        trie.update(&witness.state_writes())?;
+        trie.commit()?;
+        let block1_snapshot = trie.snapshot();
+
+        trie.rollback(&genesis_snapshot);
+        assert_eq!(trie.root(), GenesisConfig::DEVNET6_STATE_ROOT);
+        for (key, pre_value, post_value) in witness_values(&witness) {
+            assert_eq!(trie.get(&key)?, pre_value.as_ref());
+            assert_eq!(block1_snapshot.trie().get(&key)?, post_value.as_ref());
+        }
+
+        trie.rollback(&block1_snapshot);
+        assert_eq!(trie.root(), BLOCK1_STATE_ROOT);
+        Ok(())
+    }
+
+    #[test]
+    fn read_snapshot_from_other_thread() -> anyhow::Result<()> {
+        let witness = read_witness()?;
+        let values = witness_values(&witness);
+
+        let mut trie = VerkleTrie::new();
+        trie.update(&read_genesis()?.into_state_writes())?;
+        trie.commit()?;
+        let genesis_snapshot = trie.snapshot();
+
+        thread::scope(|scope| -> anyhow::Result<()> {
+            let reader = scope.spawn(|| -> anyhow::Result<()> {
+                for (key, pre_value, _) in &values {
+                    assert_eq!(genesis_snapshot.trie().get(key)?, pre_value.as_ref());
+                }
+                Ok(())
+            });
+
+            trie.update(&witness.state_writes())?;
+            assert_eq!(trie.root(), BLOCK1_STATE_ROOT);
+            reader.join().expect("reader thread shouldn't panic")
+        })?;
+
+        assert_eq!(genesis_snapshot.root(), GenesisConfig::DEVNET6_STATE_ROOT);
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy_primitives::{keccak256, Address, U256};
+
+use crate::{Stem, TrieKey, TrieValue};
+
+use super::{storage::AccountStorageLayout, StateWrites, StemStateWrite};
+
+/// Collects account, storage and code changes and groups them by stem into [StateWrites].
+///
+/// Writing the same key multiple times keeps only the last value.
+#[derive(Debug, Clone, Default)]
+pub struct StateWritesBuilder {
+    state_writes: BTreeMap<Stem, StemStateWrite>,
+}
+
+impl StateWritesBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes the value to the given key.
+    pub fn insert(&mut self, key: TrieKey, value: TrieValue) {
+        let stem = key.stem();
+        self.state_writes
+            .entry(stem)
+            .or_insert_with(|| StemStateWrite {
+                stem,
+                writes: HashMap::new(),
+            })
+            .writes
+            .insert(key.suffix(), value);
+    }
+
+    /// Writes the account header of the account without code: version, balance, nonce and the
+    /// hash of empty code.
+    ///
+    /// Code can be set afterwards with [Self::set_code].
+    pub fn create_account(&mut self, address: Address, balance: U256, nonce: U256) {
+        let storage_layout = AccountStorageLayout::new(address);
+        self.insert(storage_layout.version_key(), U256::ZERO.into());
+        self.insert(storage_layout.balance_key(), balance.into());
+        self.insert(storage_layout.nonce_key(), nonce.into());
+        self.insert(storage_layout.code_hash_key(), keccak256([]).into());
+    }
+
+    pub fn set_balance(&mut self, address: Address, balance: U256) {
+        let storage_layout = AccountStorageLayout::new(address);
+        self.insert(storage_layout.balance_key(), balance.into());
+    }
+
+    pub fn set_nonce(&mut self, address: Address, nonce: U256) {
+        let storage_layout = AccountStorageLayout::new(address);
+        self.insert(storage_layout.nonce_key(), nonce.into());
+    }
+
+    /// Writes the code hash, code size and all code chunks.
+    pub fn set_code(&mut self, address: Address, code: &[u8]) {
+        let storage_layout = AccountStorageLayout::new(address);
+        self.insert(storage_layout.code_hash_key(), keccak256(code).into());
+        self.insert(
+            storage_layout.code_size_key(),
+            U256::from(code.len()).into(),
+        );
+        for (key, value) in storage_layout.chunkify_code(code) {
+            self.insert(key, value);
+        }
+    }
+
+    pub fn set_storage(&mut self, address: Address, storage_key: U256, value: TrieValue) {
+        let storage_layout = AccountStorageLayout::new(address);
+        self.insert(storage_layout.storage_slot_key(storage_key), value);
+    }
+
+    /// Returns the writes, sorted by stem.
+    pub fn build(self) -> StateWrites {
+        StateWrites::new(self.state_writes.into_values().collect())
+    }
+}
+
+impl From<StateWritesBuilder> for StateWrites {
+    fn from(builder: StateWritesBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{address, B256};
+
+    use crate::verkle::VerkleTrie;
+
+    use super::*;
+
+    #[test]
+    fn groups_writes_by_stem() {
+        let address = address!("fffffffffffffffffffffffffffffffffffffffe");
+        let storage_layout = AccountStorageLayout::new(address);
+
+        let mut builder = StateWritesBuilder::new();
+        builder.create_account(address, U256::from(1), U256::ZERO);
+        builder.set_balance(address, U256::from(2));
+        builder.set_nonce(address, U256::from(3));
+        builder.set_storage(address, U256::ZERO, U256::from(4).into());
+        let state_writes = builder.build();
+
+        // All writes are in the account's header stem
+        assert_eq!(state_writes.len(), 1);
+        let stem_state_write = &state_writes[0];
+        assert_eq!(
+            &stem_state_write.stem,
+            storage_layout.account_storage_stem()
+        );
+        assert_eq!(
+            stem_state_write.writes,
+            HashMap::from([
+                (storage_layout.version_key().suffix(), U256::ZERO.into()),
+                (storage_layout.balance_key().suffix(), U256::from(2).into()),
+                (storage_layout.nonce_key().suffix(), U256::from(3).into()),
+                (
+                    storage_layout.code_hash_key().suffix(),
+                    keccak256([]).into()
+                ),
+                (
+                    storage_layout.storage_slot_key(U256::ZERO).suffix(),
+                    U256::from(4).into()
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn set_code() -> anyhow::Result<()> {
+        let address = address!("fffffffffffffffffffffffffffffffffffffffe");
+        let storage_layout = AccountStorageLayout::new(address);
+        // PUSH32 at the end of the first chunk, followed by 32 bytes of push data
+        let code = [vec![0; 30], vec![0x7f], vec![0xff; 32]].concat();
+
+        let mut builder = StateWritesBuilder::new();
+        builder.create_account(address, U256::ZERO, U256::ZERO);
+        builder.set_code(address, &code);
+        let state_writes = builder.build();
+
+        let mut trie = VerkleTrie::new();
+        trie.update(&state_writes)?;
+        assert_eq!(
+            trie.get(&storage_layout.code_hash_key())?,
+            Some(&keccak256(&code).into())
+        );
+        assert_eq!(
+            trie.get(&storage_layout.code_size_key())?,
+            Some(&U256::from(63).into())
+        );
+        for (key, value) in storage_layout.chunkify_code(&code) {
+            assert_eq!(trie.get(&key)?, Some(&value));
+        }
+        // Second chunk starts with 31 bytes of push data
+        assert_eq!(
+            trie.get(&storage_layout.code_key(1))?,
+            Some(&B256::from_slice(&[vec![31], vec![0xff; 31]].concat()).into())
+        );
+        assert_eq!(trie.code(address)?, Some(code.into()));
+        Ok(())
+    }
+}
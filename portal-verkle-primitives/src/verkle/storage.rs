@@ -9,8 +9,13 @@ use crate::{
     ScalarField, Stem, TrieKey, TrieValue, CRS,
 };
 
+use super::error::CodeChunksError;
+
 type Address32 = B256;
 
+/// The number of code bytes in each code chunk.
+pub(crate) const CODE_CHUNK_SIZE: usize = 31;
+
 pub struct AccountStorageLayout {
     address32: Address32,
     base_storage_stem: Stem,
@@ -64,30 +69,76 @@ impl AccountStorageLayout {
     }
 
     pub fn chunkify_code(&self, code: &[u8]) -> Vec<(TrieKey, TrieValue)> {
-        const PUSH_OFFSET: u8 = 95;
-        const PUSH1: u8 = PUSH_OFFSET + 1;
-        const PUSH32: u8 = PUSH_OFFSET + 32;
-
         let mut remaining_push_data = 0u8;
         let mut result = vec![];
-        for (chunk_id, chunk) in code.chunks(31).enumerate() {
+        for (chunk_id, chunk) in code.chunks(CODE_CHUNK_SIZE).enumerate() {
             let mut value = Vec::with_capacity(32);
-            value.push(remaining_push_data.min(31));
+            value.push(remaining_push_data.min(CODE_CHUNK_SIZE as u8));
             value.extend(chunk);
             value.resize(32, 0);
             result.push((self.code_key(chunk_id), B256::from_slice(&value).into()));
 
-            // update remaining_push_data for next chunk
-            for chunk_byte in chunk {
-                if remaining_push_data > 0 {
-                    remaining_push_data -= 1;
-                } else if (PUSH1..=PUSH32).contains(chunk_byte) {
-                    remaining_push_data = chunk_byte - PUSH_OFFSET;
-                }
-            }
+            remaining_push_data = remaining_push_data_after(chunk, remaining_push_data);
         }
         result
     }
+
+    /// The inverse of [Self::chunkify_code].
+    ///
+    /// Rebuilds the code of the given size from its chunks, checking that the leading push data
+    /// byte of each chunk matches the preceding code and that the last chunk is zero padded.
+    pub fn dechunkify_code(
+        code_size: usize,
+        chunks: &[TrieValue],
+    ) -> Result<Vec<u8>, CodeChunksError> {
+        let expected_chunks = code_size.div_ceil(CODE_CHUNK_SIZE);
+        if chunks.len() != expected_chunks {
+            return Err(CodeChunksError::InvalidChunkCount {
+                code_size,
+                expected: expected_chunks,
+                actual: chunks.len(),
+            });
+        }
+
+        let mut remaining_push_data = 0u8;
+        let mut code = Vec::with_capacity(code_size);
+        for (chunk_id, chunk) in chunks.iter().enumerate() {
+            let expected = remaining_push_data.min(CODE_CHUNK_SIZE as u8);
+            if chunk[0] != expected {
+                return Err(CodeChunksError::InvalidPushDataOffset {
+                    chunk_id,
+                    expected,
+                    actual: chunk[0],
+                });
+            }
+
+            let chunk_code_size = usize::min(CODE_CHUNK_SIZE, code_size - code.len());
+            let (chunk_code, padding) = chunk[1..].split_at(chunk_code_size);
+            if padding.iter().any(|byte| *byte != 0) {
+                return Err(CodeChunksError::NonZeroPadding { chunk_id });
+            }
+            code.extend_from_slice(chunk_code);
+
+            remaining_push_data = remaining_push_data_after(chunk_code, remaining_push_data);
+        }
+        Ok(code)
+    }
+}
+
+/// Returns the number of push data bytes that remain after the given code.
+fn remaining_push_data_after(code: &[u8], mut remaining_push_data: u8) -> u8 {
+    const PUSH_OFFSET: u8 = 95;
+    const PUSH1: u8 = PUSH_OFFSET + 1;
+    const PUSH32: u8 = PUSH_OFFSET + 32;
+
+    for byte in code {
+        if remaining_push_data > 0 {
+            remaining_push_data -= 1;
+        } else if (PUSH1..=PUSH32).contains(byte) {
+            remaining_push_data = byte - PUSH_OFFSET;
+        }
+    }
+    remaining_push_data
 }
 
 fn tree_key(address: &Address32, storage_pos: &U256) -> TrieKey {
@@ -115,3 +166,90 @@ fn tree_key(address: &Address32, storage_pos: &U256) -> TrieKey {
     key.set_suffix(key_suffix);
     key
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::address;
+
+    use super::*;
+
+    fn chunk_values(code: &[u8]) -> Vec<TrieValue> {
+        let storage_layout =
+            AccountStorageLayout::new(address!("fffffffffffffffffffffffffffffffffffffffe"));
+        storage_layout
+            .chunkify_code(code)
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect()
+    }
+
+    #[test]
+    fn dechunkify_code() -> anyhow::Result<()> {
+        // PUSH1 0x80 PUSH1 0x40 MSTORE, followed by PUSH32 that spans 3 chunks
+        let code = [
+            vec![0x60, 0x80, 0x60, 0x40, 0x52],
+            vec![0; 55],
+            vec![0x7f],
+            vec![0xff; 32],
+            vec![0x00],
+        ]
+        .concat();
+        for code_size in 0..=code.len() {
+            let code = &code[..code_size];
+            assert_eq!(
+                AccountStorageLayout::dechunkify_code(code_size, &chunk_values(code))?,
+                code
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn dechunkify_code_invalid_chunk_count() {
+        let chunks = chunk_values(&[0; 40]);
+        assert!(matches!(
+            AccountStorageLayout::dechunkify_code(70, &chunks),
+            Err(CodeChunksError::InvalidChunkCount {
+                code_size: 70,
+                expected: 3,
+                actual: 2,
+            })
+        ));
+        assert!(matches!(
+            AccountStorageLayout::dechunkify_code(31, &chunks),
+            Err(CodeChunksError::InvalidChunkCount {
+                code_size: 31,
+                expected: 1,
+                actual: 2,
+            })
+        ));
+    }
+
+    #[test]
+    fn dechunkify_code_invalid_push_data_offset() {
+        // PUSH4 with 2 bytes of push data in the first chunk and 2 in the second
+        let mut code = [0; 40];
+        code[28] = 0x63;
+        let mut chunks = chunk_values(&code);
+        assert_eq!(chunks[1][0], 2);
+
+        chunks[1] = chunk_values(&[0; 40])[1];
+        assert!(matches!(
+            AccountStorageLayout::dechunkify_code(code.len(), &chunks),
+            Err(CodeChunksError::InvalidPushDataOffset {
+                chunk_id: 1,
+                expected: 2,
+                actual: 0,
+            })
+        ));
+    }
+
+    #[test]
+    fn dechunkify_code_non_zero_padding() {
+        let chunks = chunk_values(&[1; 40]);
+        assert!(matches!(
+            AccountStorageLayout::dechunkify_code(39, &chunks),
+            Err(CodeChunksError::NonZeroPadding { chunk_id: 1 })
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufReader, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::Path,
+    sync::{Mutex, RwLock},
+};
+
+use alloy_primitives::B256;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use ssz_types::{typenum, VariableList};
+
+use crate::{verkle::error::NodeStoreError, Point};
+
+use super::{NodeStore, StoredNode};
+
+/// The [NodeStore] that appends all nodes to a single file.
+///
+/// Each record is the SSZ encoded path and node, prefixed with its length (4 bytes, little
+/// endian). Records are never modified, so file keeps all versions of all nodes.
+///
+/// The location of each record is kept in memory and it's rebuilt by reading the whole file when
+/// the store is opened. The index takes roughly 100 bytes per stored node, which makes this store
+/// suitable for tests and smaller tries, but not for the full state of the mainnet.
+pub struct FileNodeStore {
+    file: Mutex<File>,
+    index: RwLock<HashMap<(Vec<u8>, B256), u64>>,
+}
+
+#[derive(Encode, Decode)]
+struct Record {
+    path: VariableList<u8, typenum::U32>,
+    node: StoredNode,
+}
+
+impl FileNodeStore {
+    /// Opens the file (creating it if it doesn't exist) and indexes all of its records.
+    ///
+    /// The record at the end of the file that wasn't fully written (e.g. because of the crash
+    /// during the write) is removed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, NodeStoreError> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let mut index = HashMap::new();
+        let mut reader = BufReader::new(&mut file);
+        let mut offset = 0;
+        loop {
+            let mut len_bytes = [0; 4];
+            match reader.read_exact(&mut len_bytes) {
+                Ok(()) => {}
+                Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err.into()),
+            }
+            let len = u32::from_le_bytes(len_bytes);
+            let mut record_bytes = vec![0; len as usize];
+            match reader.read_exact(&mut record_bytes) {
+                Ok(()) => {}
+                Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err.into()),
+            }
+            let record = Record::from_ssz_bytes(&record_bytes)?;
+            index.insert(
+                (record.path.to_vec(), record.node.commitment().into()),
+                offset,
+            );
+            offset += (len_bytes.len() + record_bytes.len()) as u64;
+        }
+        drop(reader);
+
+        // Remove the partially written record
+        if file.metadata()?.len() > offset {
+            file.set_len(offset)?;
+        }
+
+        Ok(Self {
+            file: Mutex::new(file),
+            index: RwLock::new(index),
+        })
+    }
+
+    fn read_record(file: &mut File, offset: u64) -> Result<Record, NodeStoreError> {
+        file.seek(SeekFrom::Start(offset))?;
+        let mut len_bytes = [0; 4];
+        file.read_exact(&mut len_bytes)?;
+        let mut record_bytes = vec![0; u32::from_le_bytes(len_bytes) as usize];
+        file.read_exact(&mut record_bytes)?;
+        Ok(Record::from_ssz_bytes(&record_bytes)?)
+    }
+}
+
+impl NodeStore for FileNodeStore {
+    fn load(&self, path: &[u8], commitment: &Point) -> Result<Option<StoredNode>, NodeStoreError> {
+        let Some(offset) = self
+            .index
+            .read()
+            .expect("lock shouldn't be poisoned")
+            .get(&(path.to_vec(), commitment.into()))
+            .copied()
+        else {
+            return Ok(None);
+        };
+        let mut file = self.file.lock().expect("lock shouldn't be poisoned");
+        Ok(Some(Self::read_record(&mut file, offset)?.node))
+    }
+
+    fn save(&self, path: &[u8], node: StoredNode) -> Result<(), NodeStoreError> {
+        let key = (path.to_vec(), node.commitment().into());
+        if self
+            .index
+            .read()
+            .expect("lock shouldn't be poisoned")
+            .contains_key(&key)
+        {
+            return Ok(());
+        }
+
+        let record = Record {
+            path: VariableList::new(path.to_vec()).map_err(|_| NodeStoreError::InvalidPath)?,
+            node,
+        };
+        let record_bytes = record.as_ssz_bytes();
+
+        let mut file = self.file.lock().expect("lock shouldn't be poisoned");
+        let offset = file.seek(SeekFrom::End(0))?;
+        file.write_all(&(record_bytes.len() as u32).to_le_bytes())?;
+        file.write_all(&record_bytes)?;
+        self.index
+            .write()
+            .expect("lock shouldn't be poisoned")
+            .insert(key, offset);
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), NodeStoreError> {
+        let mut file = self.file.lock().expect("lock shouldn't be poisoned");
+        file.flush()?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, io::BufReader};
+
+    use alloy_primitives::b256;
+    use rstest::rstest;
+
+    use crate::{
+        verkle::{genesis_config::GenesisConfig, VerkleTrie},
+        witness::ExecutionWitness,
+        TrieKey,
+    };
+
+    use super::*;
+
+    #[test]
+    fn devnet6_block1() -> anyhow::Result<()> {
+        let path = env::temp_dir().join(format!("verkle-node-store-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let reader = BufReader::new(File::open("../testdata/genesis.json")?);
+        let genesis_config: GenesisConfig = serde_json::from_reader(reader)?;
+        let reader = BufReader::new(File::open("../testdata/devnet6_block1_witness.json")?);
+        let witness: ExecutionWitness = serde_json::from_reader(reader)?;
+        let block1_state_root =
+            b256!("5a65582e323fb83ed40438a0c33fa6ebfbc7f45e4c29d112b0142cfeb63f82af");
+
+        let mut trie = VerkleTrie::with_store(FileNodeStore::open(&path)?);
+        trie.update(&genesis_config.into_state_writes())?;
+        trie.commit()?;
+        drop(trie);
+
+        let mut trie = VerkleTrie::open(
+            FileNodeStore::open(&path)?,
+            &GenesisConfig::DEVNET6_STATE_ROOT,
+        )?;
+        trie.update(&witness.state_writes())?;
+        assert_eq!(trie.root(), block1_state_root);
+        trie.commit()?;
+        drop(trie);
+
+        let trie = VerkleTrie::open(FileNodeStore::open(&path)?, &block1_state_root)?;
+        for stem_state_diff in witness.state_diff.iter() {
+            for suffix_diff in stem_state_diff.suffix_diffs.iter() {
+                let key = TrieKey::from_stem_and_suffix(&stem_state_diff.stem, suffix_diff.suffix);
+                assert_eq!(trie.get(&key)?, suffix_diff.new_value.as_ref());
+            }
+        }
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::partial_length(vec![7, 0])]
+    #[case::partial_record(vec![100, 0, 0, 0, 1, 2, 3])]
+    fn partially_written_record(#[case] partial_record: Vec<u8>) -> anyhow::Result<()> {
+        let path = env::temp_dir().join(format!(
+            "verkle-node-store-partial-{}-{}",
+            partial_record.len(),
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        let reader = BufReader::new(File::open("../testdata/genesis.json")?);
+        let genesis_config: GenesisConfig = serde_json::from_reader(reader)?;
+        let reader = BufReader::new(File::open("../testdata/devnet6_block1_witness.json")?);
+        let witness: ExecutionWitness = serde_json::from_reader(reader)?;
+
+        let mut trie = VerkleTrie::with_store(FileNodeStore::open(&path)?);
+        trie.update(&genesis_config.into_state_writes())?;
+        trie.commit()?;
+        drop(trie);
+        let file_len = fs::metadata(&path)?.len();
+
+        // Simulate the crash in the middle of writing the record
+        OpenOptions::new()
+            .append(true)
+            .open(&path)?
+            .write_all(&partial_record)?;
+
+        let mut trie = VerkleTrie::open(
+            FileNodeStore::open(&path)?,
+            &GenesisConfig::DEVNET6_STATE_ROOT,
+        )?;
+        assert_eq!(fs::metadata(&path)?.len(), file_len);
+
+        // New records are readable after reopening
+        trie.update(&witness.state_writes())?;
+        let root = trie.root();
+        trie.commit()?;
+        drop(trie);
+        let trie = VerkleTrie::open(FileNodeStore::open(&path)?, &root)?;
+        for stem_state_diff in witness.state_diff.iter() {
+            for suffix_diff in stem_state_diff.suffix_diffs.iter() {
+                let key = TrieKey::from_stem_and_suffix(&stem_state_diff.stem, suffix_diff.suffix);
+                assert_eq!(trie.get(&key)?, suffix_diff.new_value.as_ref());
+            }
+        }
+
+        fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use alloy_primitives::B256;
+
+use crate::{verkle::error::NodeStoreError, Point};
+
+use super::{NodeStore, StoredNode};
+
+/// The [NodeStore] that keeps all nodes in memory.
+#[derive(Default)]
+pub struct MemoryNodeStore {
+    nodes: RwLock<HashMap<(Vec<u8>, B256), StoredNode>>,
+}
+
+impl MemoryNodeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.read().expect("lock shouldn't be poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl NodeStore for MemoryNodeStore {
+    fn load(&self, path: &[u8], commitment: &Point) -> Result<Option<StoredNode>, NodeStoreError> {
+        Ok(self
+            .nodes
+            .read()
+            .expect("lock shouldn't be poisoned")
+            .get(&(path.to_vec(), commitment.into()))
+            .cloned())
+    }
+
+    fn save(&self, path: &[u8], node: StoredNode) -> Result<(), NodeStoreError> {
+        self.nodes
+            .write()
+            .expect("lock shouldn't be poisoned")
+            .insert((path.to_vec(), node.commitment().into()), node);
+        Ok(())
+    }
+}
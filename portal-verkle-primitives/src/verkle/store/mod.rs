@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use ssz_derive::{Decode, Encode};
+
+use crate::{constants::VERKLE_NODE_WIDTH, ssz::SparseVector, Point, Stem, TrieValue};
+
+use super::error::NodeStoreError;
+
+pub use file::FileNodeStore;
+pub use memory::MemoryNodeStore;
+
+mod file;
+mod memory;
+
+/// The storage of the trie nodes.
+///
+/// Nodes are identified by their path from the root node and their commitment, which allows
+/// storing multiple versions of the node at the same path.
+pub trait NodeStore {
+    /// Loads the node with the given path and commitment, or `None` if it isn't stored.
+    fn load(&self, path: &[u8], commitment: &Point) -> Result<Option<StoredNode>, NodeStoreError>;
+
+    /// Saves the node at the given path.
+    fn save(&self, path: &[u8], node: StoredNode) -> Result<(), NodeStoreError>;
+
+    /// Makes sure that all saved nodes are persisted.
+    fn flush(&self) -> Result<(), NodeStoreError> {
+        Ok(())
+    }
+}
+
+impl<T: NodeStore + ?Sized> NodeStore for Arc<T> {
+    fn load(&self, path: &[u8], commitment: &Point) -> Result<Option<StoredNode>, NodeStoreError> {
+        self.as_ref().load(path, commitment)
+    }
+
+    fn save(&self, path: &[u8], node: StoredNode) -> Result<(), NodeStoreError> {
+        self.as_ref().save(path, node)
+    }
+
+    fn flush(&self) -> Result<(), NodeStoreError> {
+        self.as_ref().flush()
+    }
+}
+
+/// The node, in the form in which it's stored in the [NodeStore].
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[ssz(enum_behaviour = "union")]
+pub enum StoredNode {
+    Branch(Arc<StoredBranchNode>),
+    Leaf(Arc<StoredLeafNode>),
+}
+
+impl StoredNode {
+    pub fn commitment(&self) -> &Point {
+        match self {
+            Self::Branch(branch_node) => &branch_node.commitment,
+            Self::Leaf(leaf_node) => &leaf_node.commitment,
+        }
+    }
+}
+
+/// The branch node, with commitments of all non-empty children.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct StoredBranchNode {
+    pub commitment: Point,
+    pub children: SparseVector<Point, VERKLE_NODE_WIDTH>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct StoredLeafNode {
+    pub stem: Stem,
+    pub commitment: Point,
+    pub c1: Point,
+    pub c2: Point,
+    pub values: SparseVector<TrieValue, VERKLE_NODE_WIDTH>,
+}
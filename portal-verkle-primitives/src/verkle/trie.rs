@@ -1,10 +1,14 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
-use alloy_primitives::B256;
+use alloy_primitives::{Bytes, B256};
 
 use super::{
     nodes::{branch::BranchNode, Node},
-    PathToLeaf, StemStateWrite,
+    store::{MemoryNodeStore, NodeStore},
+    PathToLeaf, StemStateWrite, VerkleTrieSnapshot,
 };
 use crate::{
     ssz::TriePath,
@@ -12,22 +16,63 @@ use crate::{
     Point, Stem, TrieKey, TrieValue,
 };
 
-/// Fully in-memory implementation of the Verkle Trie.
-pub struct VerkleTrie {
+/// The Verkle Trie, whose nodes are persisted in the [NodeStore].
+///
+/// Nodes are loaded from the store only when they are needed, and they are kept in memory until
+/// the next [commit](Self::commit). By default, all nodes are kept in memory.
+///
+/// Taking the [snapshot](Self::snapshot) of the trie is cheap, as snapshots share unmodified nodes
+/// with the trie.
+pub struct VerkleTrie<S = MemoryNodeStore> {
     root_node: BranchNode,
+    store: Arc<S>,
 }
 
 impl VerkleTrie {
     pub fn new() -> Self {
+        Self::with_store(MemoryNodeStore::new())
+    }
+}
+
+impl<S: NodeStore> VerkleTrie<S> {
+    /// Creates the empty trie that persists its nodes in the provided store.
+    pub fn with_store(store: S) -> Self {
         Self {
             root_node: BranchNode::new(/* depth= */ 0),
+            store: Arc::new(store),
         }
     }
 
+    /// Opens the trie with the given root, whose nodes are persisted in the provided store.
+    pub fn open(store: S, root: &B256) -> Result<Self, VerkleTrieError> {
+        let root_commitment =
+            Point::try_from(root).map_err(|source| VerkleTrieError::InvalidRoot {
+                root: *root,
+                source,
+            })?;
+        let root_node = match Node::load(&store, &[], &root_commitment)? {
+            Node::Branch(root_node) => Arc::unwrap_or_clone(root_node),
+            _ => {
+                return Err(VerkleTrieError::MissingStoredNode {
+                    path: Bytes::new(),
+                    commitment: *root,
+                })
+            }
+        };
+        Ok(Self {
+            root_node,
+            store: Arc::new(store),
+        })
+    }
+
     pub(super) fn root_node(&self) -> &BranchNode {
         &self.root_node
     }
 
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
     pub fn root_commitment(&self) -> &Point {
         self.root_node.commitment().as_point()
     }
@@ -36,30 +81,63 @@ impl VerkleTrie {
         self.root_commitment().into()
     }
 
-    pub fn get(&self, key: &TrieKey) -> Option<&TrieValue> {
-        self.root_node.get(key)
+    pub fn get(&self, key: &TrieKey) -> Result<Option<&TrieValue>, VerkleTrieError> {
+        self.root_node.get(key, self.store.as_ref())
     }
 
-    pub fn insert(&mut self, key: &TrieKey, value: TrieValue) {
+    pub fn insert(&mut self, key: &TrieKey, value: TrieValue) -> Result<(), VerkleTrieError> {
         let stem_state_write = StemStateWrite {
             stem: key.stem(),
             writes: HashMap::from([(key.suffix(), value)]),
         };
-        self.root_node.update(&stem_state_write);
+        self.root_node
+            .update(&stem_state_write, self.store.as_ref())?;
+        Ok(())
     }
 
-    pub fn update(&mut self, state_writes: &StateWrites) -> HashSet<TriePath> {
+    pub fn update(
+        &mut self,
+        state_writes: &StateWrites,
+    ) -> Result<HashSet<TriePath>, VerkleTrieError> {
         let mut created_branches = HashSet::new();
         for stem_state_write in state_writes.iter() {
             if stem_state_write.writes.is_empty() {
                 continue;
             }
-            let (_, created_branch) = self.root_node.update(stem_state_write);
+            let (_, created_branch) = self
+                .root_node
+                .update(stem_state_write, self.store.as_ref())?;
             if let Some(created_branch) = created_branch {
                 created_branches.insert(created_branch);
             }
         }
-        created_branches
+        Ok(created_branches)
+    }
+
+    /// Saves all modified nodes to the store and unloads them from memory.
+    pub fn commit(&mut self) -> Result<(), VerkleTrieError> {
+        self.root_node.save(&[], self.store.as_ref())?;
+        self.store.flush()?;
+        self.root_node.unload_children();
+        Ok(())
+    }
+
+    /// Returns the snapshot of the current state of the trie.
+    ///
+    /// Later modifications of the trie are not visible in the snapshot.
+    pub fn snapshot(&self) -> VerkleTrieSnapshot<S> {
+        VerkleTrieSnapshot::new(Self {
+            root_node: self.root_node.clone(),
+            store: self.store.clone(),
+        })
+    }
+
+    /// Reverts the trie to the state of the snapshot.
+    ///
+    /// The snapshot stays valid, and it can be used for rollback again.
+    pub fn rollback(&mut self, snapshot: &VerkleTrieSnapshot<S>) {
+        self.root_node = snapshot.trie().root_node.clone();
+        self.store = snapshot.trie().store.clone();
     }
 
     pub fn traverse_to_leaf<'me>(
@@ -74,8 +152,10 @@ impl VerkleTrie {
         loop {
             let child_index = stem[depth];
             trie_path.push((node, child_index));
-            node = match node.get_child(child_index) {
-                Node::Empty => return Err(VerkleTrieError::NodeNotFound { stem: *stem, depth }),
+            node = match self.child(node, &stem[..=depth])? {
+                Node::Empty | Node::Unloaded(_) => {
+                    return Err(VerkleTrieError::NodeNotFound { stem: *stem, depth })
+                }
                 Node::Branch(next_node) => next_node,
                 Node::Leaf(leaf) => {
                     if leaf.stem() == stem {
@@ -91,6 +171,20 @@ impl VerkleTrie {
             depth += 1;
         }
     }
+
+    /// Returns the child of the branch node at the given path, loading it from the store if
+    /// needed.
+    pub(super) fn child<'a>(
+        &'a self,
+        branch_node: &'a BranchNode,
+        child_path: &[u8],
+    ) -> Result<&'a Node, VerkleTrieError> {
+        let child_index = child_path[branch_node.depth()];
+        match branch_node.get_child(child_index) {
+            Node::Unloaded(unloaded_node) => unloaded_node.load(child_path, self.store.as_ref()),
+            child => Ok(child),
+        }
+    }
 }
 
 impl Default for VerkleTrie {
@@ -103,7 +197,7 @@ impl Default for VerkleTrie {
 mod tests {
     use std::{fs::File, io::BufReader, str::FromStr};
 
-    use alloy_primitives::{address, b256, keccak256};
+    use alloy_primitives::{address, b256, keccak256, U256};
 
     use crate::{
         constants::{
@@ -125,7 +219,7 @@ mod tests {
         let genesis_config = read_genesis();
 
         let mut trie = VerkleTrie::new();
-        trie.update(&genesis_config.into_state_writes());
+        trie.update(&genesis_config.into_state_writes()).unwrap();
 
         assert_eq!(trie.root(), GenesisConfig::DEVNET6_STATE_ROOT)
     }
@@ -137,10 +231,14 @@ mod tests {
             b256!("5a65582e323fb83ed40438a0c33fa6ebfbc7f45e4c29d112b0142cfeb63f82af");
 
         let mut trie = VerkleTrie::new();
-        trie.update(&genesis_config.into_state_writes());
+        trie.update(&genesis_config.into_state_writes()).unwrap();
 
-        let storage_layout =
-            AccountStorageLayout::new(address!("fffffffffffffffffffffffffffffffffffffffe"));
+        let address = address!("fffffffffffffffffffffffffffffffffffffffe");
+        let storage_layout = AccountStorageLayout::new(address);
+        let parent_block_hash = TrieValue::from_str(
+            "0x3fe165c03e7a77d1e3759362ebeeb16fd964cb411ce11fbe35c7032fab5b9a8a",
+        )
+        .unwrap();
         let stem_state_write = StemStateWrite {
             stem: *storage_layout.account_storage_stem(),
             writes: HashMap::from([
@@ -148,21 +246,62 @@ mod tests {
                 (BALANCE_LEAF_KEY, TrieValue::ZERO),
                 (NONCE_LEAF_KEY, TrieValue::ZERO),
                 (CODE_KECCAK_LEAF_KEY, TrieValue::from(keccak256([]))),
-                (
-                    HEADER_STORAGE_OFFSET.byte(0),
-                    TrieValue::from_str(
-                        "0x3fe165c03e7a77d1e3759362ebeeb16fd964cb411ce11fbe35c7032fab5b9a8a",
-                    )
-                    .unwrap(),
-                ),
+                (HEADER_STORAGE_OFFSET.byte(0), parent_block_hash),
             ]),
         };
 
-        let new_branch_nodes = trie.update(&StateWrites(vec![stem_state_write]));
+        let new_branch_nodes = trie.update(&StateWrites(vec![stem_state_write])).unwrap();
         assert_eq!(
             new_branch_nodes,
             [TriePath::new(vec![0x5b]).unwrap()].into()
         );
         assert_eq!(trie.root(), block1_state_root);
+
+        let account = trie.account(address).unwrap().unwrap();
+        assert_eq!(account.balance, U256::ZERO);
+        assert_eq!(account.code_hash, keccak256([]));
+        assert_eq!(
+            trie.storage(address, U256::ZERO).unwrap(),
+            Some(parent_block_hash)
+        );
+    }
+
+    #[test]
+    fn commit_and_open() -> anyhow::Result<()> {
+        let state_writes = read_genesis().into_state_writes();
+
+        let mut trie = VerkleTrie::new();
+        trie.update(&state_writes)?;
+        trie.commit()?;
+        assert!(!trie.store().is_empty());
+        assert_eq!(trie.root(), GenesisConfig::DEVNET6_STATE_ROOT);
+
+        // Values are loaded from the store
+        for stem_state_write in state_writes.iter() {
+            for (suffix, value) in &stem_state_write.writes {
+                let key = TrieKey::from_stem_and_suffix(&stem_state_write.stem, *suffix);
+                assert_eq!(trie.get(&key)?, Some(value));
+            }
+        }
+
+        let mut trie = VerkleTrie::open(trie.store, &GenesisConfig::DEVNET6_STATE_ROOT)?;
+        let stem = state_writes[0].stem;
+        assert_eq!(trie.traverse_to_leaf(&stem)?.leaf.stem(), &stem);
+
+        let storage_layout =
+            AccountStorageLayout::new(address!("fffffffffffffffffffffffffffffffffffffffe"));
+        trie.insert(&storage_layout.nonce_key(), TrieValue::ZERO)?;
+        trie.commit()?;
+        let root = trie.root();
+
+        // Both versions are present in the store
+        let trie = VerkleTrie::open(trie.store, &GenesisConfig::DEVNET6_STATE_ROOT)?;
+        assert_eq!(trie.get(&storage_layout.nonce_key())?, None);
+        let trie = VerkleTrie::open(trie.store, &root)?;
+        assert_eq!(
+            trie.get(&storage_layout.nonce_key())?,
+            Some(&TrieValue::ZERO)
+        );
+        Ok(())
     }
 }
@@ -4,6 +4,7 @@ use alloy_primitives::hex::{encode, encode_prefixed};
 
 use super::{
     nodes::{branch::BranchNode, leaf::LeafNode, Node},
+    store::NodeStore,
     VerkleTrie,
 };
 use crate::constants::VERKLE_NODE_WIDTH;
@@ -20,7 +21,8 @@ pub trait TriePrinter {
     ) -> io::Result<()>;
 }
 
-impl TriePrinter for VerkleTrie {
+/// Only the nodes that are loaded into memory are printed.
+impl<S: NodeStore> TriePrinter for VerkleTrie<S> {
     fn print_state<W: Write>(&self, writer: &mut W) -> io::Result<()> {
         self.root_node().print_state(writer)?;
         writer.flush()
@@ -43,6 +45,10 @@ impl TriePrinter for Node {
             Node::Empty => Ok(()),
             Node::Branch(branch_node) => branch_node.print_state(writer),
             Node::Leaf(leaf_node) => leaf_node.print_state(writer),
+            Node::Unloaded(unloaded_node) => match unloaded_node.get() {
+                Some(node) => node.print_state(writer),
+                None => Ok(()),
+            },
         }
     }
 
@@ -55,6 +61,10 @@ impl TriePrinter for Node {
             Node::Empty => Ok(()),
             Node::Branch(branch_node) => branch_node.print_trie_with_identation(writer, identation),
             Node::Leaf(leaf_node) => leaf_node.print_trie_with_identation(writer, identation),
+            Node::Unloaded(unloaded_node) => match unloaded_node.get() {
+                Some(node) => node.print_trie_with_identation(writer, identation),
+                None => Ok(()),
+            },
         }
     }
 }
@@ -0,0 +1,393 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use alloy_primitives::{Bytes, B256};
+use ssz_types::VariableList;
+
+use crate::{
+    constants::{
+        LEAF_C1_INDEX, LEAF_C2_INDEX, LEAF_MARKER_INDEX, LEAF_STEM_INDEX, VERKLE_NODE_WIDTH,
+    },
+    proof::{
+        lagrange_basis::LagrangeBasis, transcript::Transcript, MultiProof, ProverMultiQuery,
+        ProverQuery,
+    },
+    witness::{ExecutionWitness, ExtensionStatus, StemStateDiff, SuffixStateDiff, VerkleProof},
+    Point, Stem, TrieKey,
+};
+
+use super::{
+    error::VerkleTrieError,
+    nodes::{branch::BranchNode, leaf::LeafNode, Node},
+    store::NodeStore,
+    StateWrites, VerkleTrie,
+};
+
+impl<S: NodeStore> VerkleTrie<S> {
+    /// Creates the execution witness for all keys that are accessed by the block.
+    ///
+    /// The keys that are written by the block are considered accessed as well. The current values
+    /// are taken from this trie (which should represent the state before the block), while new
+    /// values are taken from the state writes.
+    ///
+    /// The proof has the same structure as the one created by go-verkle's `MakeVerkleMultiProof`.
+    pub fn create_execution_witness(
+        &self,
+        accessed_keys: impl IntoIterator<Item = TrieKey>,
+        state_writes: &StateWrites,
+    ) -> Result<ExecutionWitness, VerkleTrieError> {
+        let new_values = state_writes
+            .iter()
+            .flat_map(|stem_state_write| {
+                stem_state_write.writes.iter().map(|(suffix, value)| {
+                    (
+                        TrieKey::from_stem_and_suffix(&stem_state_write.stem, *suffix),
+                        *value,
+                    )
+                })
+            })
+            .collect::<HashMap<_, _>>();
+        let keys = accessed_keys
+            .into_iter()
+            .chain(new_values.keys().copied())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let mut state_diff = vec![];
+        for keys in keys.chunk_by(|a, b| a.stem() == b.stem()) {
+            let mut suffix_diffs = vec![];
+            for key in keys {
+                suffix_diffs.push(SuffixStateDiff {
+                    suffix: key.suffix(),
+                    current_value: self.get(key)?.copied(),
+                    new_value: new_values.get(key).copied(),
+                });
+            }
+            state_diff.push(StemStateDiff {
+                stem: keys[0].stem(),
+                suffix_diffs: VariableList::new(suffix_diffs)
+                    .expect("number of suffix diffs shouldn't exceed verkle node width"),
+            });
+        }
+
+        Ok(ExecutionWitness {
+            state_diff: VariableList::new(state_diff).expect("state diff shouldn't be too long"),
+            verkle_proof: self.create_verkle_proof(&keys)?,
+        })
+    }
+
+    /// Creates the proof for the values of the provided keys.
+    ///
+    /// The keys can be in any order and duplicates are ignored. Returns error if there are no keys.
+    pub fn create_verkle_proof(&self, keys: &[TrieKey]) -> Result<VerkleProof, VerkleTrieError> {
+        let keys = keys
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        if keys.is_empty() {
+            return Err(VerkleTrieError::NoProofKeys);
+        }
+
+        let mut builder = ProofBuilder::default();
+        builder.add_branch(self, &[], self.root_node(), &keys)?;
+
+        let multiproof = MultiProof::create(
+            builder.multiquery,
+            &mut Transcript::new(Transcript::VERKLE_TRIE_LABEL),
+        )?;
+        Ok(VerkleProof {
+            other_stems: VariableList::new(builder.other_stems)
+                .expect("number of other stems shouldn't exceed number of stems"),
+            depth_extension_present: Bytes::from(builder.depth_extension_present),
+            commitments_by_path: VariableList::new(
+                builder.commitments_by_path.into_values().collect(),
+            )
+            .expect("number of commitments shouldn't exceed the limit"),
+            d: multiproof.g_commitment,
+            ipa_proof: multiproof.ipa_proof,
+        })
+    }
+}
+
+/// Collects all parts of the proof while traversing the trie.
+///
+/// The traversal is depth-first, in the order of the keys, which makes extension statuses and
+/// other stems ordered by stem.
+#[derive(Default)]
+struct ProofBuilder {
+    multiquery: ProverMultiQuery,
+    openings: HashSet<(B256, u8)>,
+    other_stems: Vec<Stem>,
+    depth_extension_present: Vec<u8>,
+    commitments_by_path: BTreeMap<Vec<u8>, Point>,
+}
+
+impl ProofBuilder {
+    fn open(&mut self, commitment: &Point, poly: &LagrangeBasis, z: u8) {
+        if self.openings.insert((B256::from(commitment), z)) {
+            self.multiquery.push(ProverQuery {
+                poly: poly.clone(),
+                commitment: Some(commitment.clone()),
+                z,
+            });
+        }
+    }
+
+    fn add_extension_statuses(
+        &mut self,
+        keys: &[TrieKey],
+        depth: usize,
+        status: impl Fn(&Stem) -> ExtensionStatus,
+    ) {
+        for keys in keys.chunk_by(|a, b| a.stem() == b.stem()) {
+            self.depth_extension_present
+                .push(status(&keys[0].stem()).encode(depth));
+        }
+    }
+
+    /// Each branch node first opens all of its accessed children, and only then the children are
+    /// traversed.
+    fn add_branch<S: NodeStore>(
+        &mut self,
+        trie: &VerkleTrie<S>,
+        path: &[u8],
+        branch: &BranchNode,
+        keys: &[TrieKey],
+    ) -> Result<(), VerkleTrieError> {
+        let depth = branch.depth();
+        let commitment = branch.commitment().as_point();
+        let poly = branch.to_lagrange_basis();
+
+        let groups = keys
+            .chunk_by(|a, b| a[depth] == b[depth])
+            .collect::<Vec<_>>();
+        for group in &groups {
+            self.open(commitment, &poly, group[0][depth]);
+        }
+
+        for group in groups {
+            let child_index = group[0][depth];
+            let child_path = [path, &[child_index]].concat();
+            match trie.child(branch, &child_path)? {
+                Node::Empty => {
+                    self.add_extension_statuses(group, depth + 1, |_| ExtensionStatus::AbsentEmpty)
+                }
+                Node::Branch(child) => {
+                    self.commitments_by_path
+                        .insert(child_path.clone(), child.commitment().to_point());
+                    self.add_branch(trie, &child_path, child, group)?;
+                }
+                Node::Leaf(leaf) => {
+                    self.commitments_by_path
+                        .insert(child_path.clone(), leaf.commitment().to_point());
+                    self.add_leaf(&child_path, leaf, group);
+                }
+                Node::Unloaded(_) => unreachable!("Loaded node can't be unloaded"),
+            }
+        }
+        Ok(())
+    }
+
+    fn add_leaf(&mut self, path: &[u8], leaf: &LeafNode, keys: &[TrieKey]) {
+        let commitment = leaf.commitment().as_point();
+        let poly = leaf.to_lagrange_basis();
+
+        self.add_extension_statuses(keys, path.len(), |stem| {
+            if stem == leaf.stem() {
+                ExtensionStatus::Present
+            } else {
+                ExtensionStatus::AbsentOther
+            }
+        });
+
+        self.open(commitment, &poly, LEAF_MARKER_INDEX);
+        self.open(commitment, &poly, LEAF_STEM_INDEX);
+
+        let present_keys = keys
+            .iter()
+            .filter(|key| key.starts_with_stem(leaf.stem()))
+            .collect::<Vec<_>>();
+        if present_keys.is_empty() {
+            // The stem of the leaf is provided only if it's not present
+            self.other_stems.push(*leaf.stem());
+            return;
+        }
+
+        let is_c1_suffix = |key: &&TrieKey| key.suffix() < (VERKLE_NODE_WIDTH / 2) as u8;
+        let c1_poly = present_keys.iter().any(is_c1_suffix).then(|| {
+            self.open(commitment, &poly, LEAF_C1_INDEX);
+            self.commitments_by_path
+                .insert([path, &[LEAF_C1_INDEX]].concat(), leaf.c1().to_point());
+            leaf.to_c1_lagrange_basis()
+        });
+        let c2_poly = present_keys.iter().any(|key| !is_c1_suffix(key)).then(|| {
+            self.open(commitment, &poly, LEAF_C2_INDEX);
+            self.commitments_by_path
+                .insert([path, &[LEAF_C2_INDEX]].concat(), leaf.c2().to_point());
+            leaf.to_c2_lagrange_basis()
+        });
+
+        for key in present_keys {
+            let (suffix_commitment, suffix_poly) = if is_c1_suffix(&key) {
+                (leaf.c1().as_point(), &c1_poly)
+            } else {
+                (leaf.c2().as_point(), &c2_poly)
+            };
+            let suffix_poly = suffix_poly
+                .as_ref()
+                .expect("suffix polynomial should be present for accessed suffix");
+            let low_index = 2 * (key.suffix() % (VERKLE_NODE_WIDTH / 2) as u8);
+            self.open(suffix_commitment, suffix_poly, low_index);
+            self.open(suffix_commitment, suffix_poly, low_index + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader, str::FromStr};
+
+    use alloy_primitives::{address, keccak256, U256};
+
+    use crate::{
+        constants::{
+            BALANCE_LEAF_KEY, CODE_KECCAK_LEAF_KEY, HEADER_STORAGE_OFFSET, NONCE_LEAF_KEY,
+            VERSION_LEAF_KEY,
+        },
+        verkle::{genesis_config::GenesisConfig, storage::AccountStorageLayout, StemStateWrite},
+        TrieValue,
+    };
+
+    use super::*;
+
+    fn genesis_trie() -> (GenesisConfig, VerkleTrie) {
+        let reader = BufReader::new(File::open("../testdata/genesis.json").unwrap());
+        let genesis_config: GenesisConfig = serde_json::from_reader(reader).unwrap();
+        let mut trie = VerkleTrie::new();
+        trie.update(&genesis_config.clone().into_state_writes())
+            .unwrap();
+        (genesis_config, trie)
+    }
+
+    #[test]
+    fn devnet6_block1() -> anyhow::Result<()> {
+        let (_, trie) = genesis_trie();
+        let reader = BufReader::new(File::open("../testdata/devnet6_block1_witness.json")?);
+        let expected: ExecutionWitness = serde_json::from_reader(reader)?;
+
+        let storage_layout =
+            AccountStorageLayout::new(address!("fffffffffffffffffffffffffffffffffffffffe"));
+        let state_writes = StateWrites::new(vec![StemStateWrite {
+            stem: *storage_layout.account_storage_stem(),
+            writes: HashMap::from([
+                (VERSION_LEAF_KEY, TrieValue::ZERO),
+                (BALANCE_LEAF_KEY, TrieValue::ZERO),
+                (NONCE_LEAF_KEY, TrieValue::ZERO),
+                (CODE_KECCAK_LEAF_KEY, TrieValue::from(keccak256([]))),
+                (
+                    HEADER_STORAGE_OFFSET.byte(0),
+                    TrieValue::from_str(
+                        "0x3fe165c03e7a77d1e3759362ebeeb16fd964cb411ce11fbe35c7032fab5b9a8a",
+                    )?,
+                ),
+            ]),
+        }]);
+
+        let witness =
+            trie.create_execution_witness([storage_layout.code_size_key()], &state_writes)?;
+        assert_eq!(witness, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn present_and_absent_keys() -> anyhow::Result<()> {
+        let (genesis_config, trie) = genesis_trie();
+
+        let mut accessed_keys = vec![];
+        for (address, account_alloc) in genesis_config.alloc.iter().take(10) {
+            let storage_layout = AccountStorageLayout::new(*address);
+            accessed_keys.push(storage_layout.balance_key());
+            accessed_keys.push(storage_layout.nonce_key());
+            // Absent suffix in C1 and C2
+            accessed_keys.push(storage_layout.code_size_key());
+            accessed_keys.push(storage_layout.code_key(200));
+            if let Some(storage) = &account_alloc.storage {
+                accessed_keys.extend(
+                    storage
+                        .keys()
+                        .map(|storage_key| storage_layout.storage_slot_key(*storage_key)),
+                );
+            }
+        }
+        // Absent stems
+        for index in 0..10u64 {
+            let storage_layout = AccountStorageLayout::new(
+                address!("1000000000000000000000000000000000000000").create(index),
+            );
+            accessed_keys.push(storage_layout.balance_key());
+            accessed_keys.push(storage_layout.storage_slot_key(U256::from(index)));
+        }
+
+        let witness = trie.create_execution_witness(accessed_keys, &StateWrites::new(vec![]))?;
+        witness.verify(&trie.root())?;
+        Ok(())
+    }
+
+    #[test]
+    fn empty_trie() -> anyhow::Result<()> {
+        let trie = VerkleTrie::new();
+        let key = TrieKey::from_stem_and_suffix(&Stem::from([0x12; 31]), 5);
+        let state_writes = StateWrites::new(vec![StemStateWrite {
+            stem: key.stem(),
+            writes: HashMap::from([(key.suffix(), TrieValue::from(U256::from(1)))]),
+        }]);
+
+        let witness = trie.create_execution_witness([], &state_writes)?;
+        assert_eq!(
+            witness.verkle_proof.depth_extension_statuses()?,
+            vec![(1, ExtensionStatus::AbsentEmpty)]
+        );
+        witness.verify(&trie.root())?;
+        Ok(())
+    }
+
+    #[test]
+    fn no_keys() {
+        let (_, trie) = genesis_trie();
+        assert!(matches!(
+            trie.create_verkle_proof(&[]),
+            Err(VerkleTrieError::NoProofKeys)
+        ));
+        assert!(matches!(
+            trie.create_execution_witness([], &StateWrites::new(vec![])),
+            Err(VerkleTrieError::NoProofKeys)
+        ));
+    }
+
+    #[test]
+    fn unsorted_and_duplicate_keys() -> anyhow::Result<()> {
+        let (genesis_config, trie) = genesis_trie();
+        let mut keys = genesis_config
+            .alloc
+            .keys()
+            .take(5)
+            .flat_map(|address| {
+                let storage_layout = AccountStorageLayout::new(*address);
+                [storage_layout.balance_key(), storage_layout.nonce_key()]
+            })
+            .collect::<Vec<_>>();
+        keys.sort();
+        let expected = trie.create_verkle_proof(&keys)?;
+
+        let unsorted_keys = keys
+            .iter()
+            .rev()
+            .chain(keys.iter().step_by(2))
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(trie.create_verkle_proof(&unsorted_keys)?, expected);
+        Ok(())
+    }
+}
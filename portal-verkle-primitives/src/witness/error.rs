@@ -0,0 +1,37 @@
+use alloy_primitives::Bytes;
+use thiserror::Error;
+
+use crate::{proof::ProofError, EcDecodeError, TrieKey};
+
+#[derive(Debug, Error)]
+pub enum WitnessVerificationError {
+    #[error("State diff is not sorted by stem and suffix")]
+    UnsortedStateDiff,
+
+    #[error("Number of stems ({stems}) doesn't match number of extension statuses ({statuses})")]
+    ExtensionStatusCountMismatch { stems: usize, statuses: usize },
+
+    #[error("Invalid extension status: {0:#04x}")]
+    InvalidExtensionStatus(u8),
+
+    #[error("Key {0} is proven absent, but has current value")]
+    UnexpectedCurrentValue(TrieKey),
+
+    #[error("Proof of absence stem is missing")]
+    MissingProofOfAbsenceStem,
+
+    #[error("Not all proof of absence stems are used: {0} remaining")]
+    UnusedProofOfAbsenceStems(usize),
+
+    #[error("Trie structure is inconsistent at path: {0}")]
+    InconsistentTrieStructure(Bytes),
+
+    #[error("Expected {expected} commitments, but received {actual}")]
+    CommitmentsCountMismatch { expected: usize, actual: usize },
+
+    #[error("Pre-state root is not a valid commitment: {0}")]
+    InvalidPreStateRoot(#[source] EcDecodeError),
+
+    #[error("MultiPointProof is invalid: {0}")]
+    InvalidMultiPointProof(#[source] ProofError),
+}
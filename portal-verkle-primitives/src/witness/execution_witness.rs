@@ -0,0 +1,176 @@
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+
+use crate::{
+    proof::{transcript::Transcript, VerifierMultiQuery},
+    verkle::{StateWrites, StemStateWrite},
+    Point, TrieKey, TrieValue,
+};
+
+use super::{reconstruction::ReconstructedTrie, StateDiff, VerkleProof, WitnessVerificationError};
+
+/// The execution witness, as defined in [EIP-6800](https://eips.ethereum.org/EIPS/eip-6800).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ExecutionWitness {
+    pub state_diff: StateDiff,
+    pub verkle_proof: VerkleProof,
+}
+
+impl ExecutionWitness {
+    /// Returns all accessed keys, together with their values before the block was executed.
+    ///
+    /// Returns error if keys are not sorted.
+    pub fn pre_state(&self) -> Result<Vec<(TrieKey, Option<TrieValue>)>, WitnessVerificationError> {
+        let keys = self
+            .state_diff
+            .iter()
+            .flat_map(|stem_state_diff| {
+                stem_state_diff.suffix_diffs.iter().map(|suffix_diff| {
+                    (
+                        TrieKey::from_stem_and_suffix(&stem_state_diff.stem, suffix_diff.suffix),
+                        suffix_diff.current_value,
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        if keys.windows(2).any(|window| window[0].0 >= window[1].0) {
+            return Err(WitnessVerificationError::UnsortedStateDiff);
+        }
+        Ok(keys)
+    }
+
+    /// Returns all writes from the state diff, i.e. the keys that have new value.
+    pub fn state_writes(&self) -> StateWrites {
+        StateWrites::new(
+            self.state_diff
+                .iter()
+                .map(|stem_state_diff| StemStateWrite {
+                    stem: stem_state_diff.stem,
+                    writes: stem_state_diff
+                        .suffix_diffs
+                        .iter()
+                        .filter_map(|suffix_diff| {
+                            suffix_diff
+                                .new_value
+                                .map(|new_value| (suffix_diff.suffix, new_value))
+                        })
+                        .collect(),
+                })
+                .filter(|stem_state_write| !stem_state_write.writes.is_empty())
+                .collect(),
+        )
+    }
+
+    /// Creates the multiquery that should be proven by the verkle proof.
+    pub fn verifier_multiquery(
+        &self,
+        pre_state_root: &Point,
+    ) -> Result<VerifierMultiQuery, WitnessVerificationError> {
+        let keys = self.pre_state()?;
+        ReconstructedTrie::new(self, pre_state_root.clone())?.verifier_multiquery(&keys)
+    }
+
+    /// Verifies that current values from the state diff are present in the trie with provided
+    /// root.
+    pub fn verify(&self, pre_state_root: &B256) -> Result<(), WitnessVerificationError> {
+        let pre_state_root = Point::try_from(pre_state_root)
+            .map_err(WitnessVerificationError::InvalidPreStateRoot)?;
+        let multiquery = self.verifier_multiquery(&pre_state_root)?;
+        self.verkle_proof
+            .multiproof()
+            .verify(
+                multiquery,
+                &mut Transcript::new(Transcript::VERKLE_TRIE_LABEL),
+            )
+            .map_err(WitnessVerificationError::InvalidMultiPointProof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use alloy_primitives::{b256, Bytes};
+    use ssz::{Decode, Encode};
+
+    use crate::verkle::genesis_config::GenesisConfig;
+
+    use super::*;
+
+    /// Reads the execution witness from the kaustinen devnet-6, block 1
+    fn devnet6_block1_witness() -> ExecutionWitness {
+        let reader = BufReader::new(File::open("../testdata/devnet6_block1_witness.json").unwrap());
+        serde_json::from_reader(reader).unwrap()
+    }
+
+    #[test]
+    fn devnet6_block1() -> anyhow::Result<()> {
+        let witness = devnet6_block1_witness();
+        witness.verify(&GenesisConfig::DEVNET6_STATE_ROOT)?;
+        Ok(())
+    }
+
+    #[test]
+    fn json_round_trip() -> anyhow::Result<()> {
+        let witness = devnet6_block1_witness();
+        let json = serde_json::to_string(&witness)?;
+        assert_eq!(serde_json::from_str::<ExecutionWitness>(&json)?, witness);
+        Ok(())
+    }
+
+    #[test]
+    fn go_verkle_json() -> anyhow::Result<()> {
+        let reader = BufReader::new(File::open("../testdata/devnet6_block1_witness.json")?);
+        let go_verkle_json: serde_json::Value = serde_json::from_reader(reader)?;
+        let witness: ExecutionWitness = serde_json::from_value(go_verkle_json.clone())?;
+
+        let json = serde_json::to_value(&witness)?;
+        assert!(json["verkleProof"]["ipaProof"]
+            .get("finalEvaluation")
+            .is_some());
+        assert_eq!(json, go_verkle_json);
+        Ok(())
+    }
+
+    #[test]
+    fn ssz_round_trip() {
+        let witness = devnet6_block1_witness();
+        let decoded = ExecutionWitness::from_ssz_bytes(&witness.as_ssz_bytes()).unwrap();
+        assert_eq!(decoded, witness);
+    }
+
+    #[test]
+    fn wrong_root() {
+        let witness = devnet6_block1_witness();
+        let wrong_root = b256!("5a65582e323fb83ed40438a0c33fa6ebfbc7f45e4c29d112b0142cfeb63f82af");
+        assert!(matches!(
+            witness.verify(&wrong_root),
+            Err(WitnessVerificationError::InvalidMultiPointProof(_))
+        ));
+    }
+
+    #[test]
+    fn present_value_for_absent_stem() {
+        let mut witness = devnet6_block1_witness();
+        witness.state_diff[0].suffix_diffs[0].current_value = Some(TrieValue::ZERO);
+        assert!(matches!(
+            witness.verify(&GenesisConfig::DEVNET6_STATE_ROOT),
+            Err(WitnessVerificationError::UnexpectedCurrentValue(_))
+        ));
+    }
+
+    #[test]
+    fn missing_extension_status() {
+        let mut witness = devnet6_block1_witness();
+        witness.verkle_proof.depth_extension_present = Bytes::new();
+        assert!(matches!(
+            witness.verify(&GenesisConfig::DEVNET6_STATE_ROOT),
+            Err(WitnessVerificationError::ExtensionStatusCountMismatch {
+                stems: 1,
+                statuses: 0
+            })
+        ));
+    }
+}
@@ -0,0 +1,12 @@
+pub use error::WitnessVerificationError;
+pub use execution_witness::ExecutionWitness;
+pub use state_diff::{StateDiff, StemStateDiff, SuffixStateDiff, SuffixStateDiffs};
+pub use verkle_proof::{CommitmentsByPath, ExtensionStatus, OtherStems, VerkleProof};
+
+pub(crate) use reconstruction::{ReconstructedLeaf, ReconstructedTrie};
+
+mod error;
+mod execution_witness;
+mod reconstruction;
+mod state_diff;
+mod verkle_proof;
@@ -0,0 +1,320 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use alloy_primitives::{Bytes, B256};
+
+use crate::{
+    constants::{
+        LEAF_C1_INDEX, LEAF_C2_INDEX, LEAF_MARKER_INDEX, LEAF_STEM_INDEX, VERKLE_NODE_WIDTH,
+    },
+    proof::{VerifierMultiQuery, VerifierQuery},
+    Point, ScalarField, Stem, TrieKey, TrieValue, TrieValueSplit,
+};
+
+use super::{ExecutionWitness, ExtensionStatus, WitnessVerificationError};
+
+/// The leaf node, as described by the execution witness.
+pub(crate) struct ReconstructedLeaf {
+    pub stem: Stem,
+    pub commitment: Point,
+    pub c1: Option<Point>,
+    pub c2: Option<Point>,
+}
+
+/// The part of the trie that is described by the execution witness.
+///
+/// All nodes are identified by their path from the root node. Paths of the leaf's C1 and C2
+/// commitments are leaf's path followed by [LEAF_C1_INDEX] and [LEAF_C2_INDEX].
+pub(crate) struct ReconstructedTrie {
+    pub root: Point,
+    pub branches: BTreeMap<Vec<u8>, Point>,
+    pub leaves: BTreeMap<Vec<u8>, ReconstructedLeaf>,
+    pub empty: BTreeSet<Vec<u8>>,
+}
+
+enum CommitmentTarget {
+    Branch,
+    Leaf,
+    C1,
+    C2,
+}
+
+impl ReconstructedTrie {
+    pub fn new(witness: &ExecutionWitness, root: Point) -> Result<Self, WitnessVerificationError> {
+        let state_diff = &witness.state_diff;
+        let verkle_proof = &witness.verkle_proof;
+
+        let statuses = verkle_proof.depth_extension_statuses()?;
+        if statuses.len() != state_diff.len() {
+            return Err(WitnessVerificationError::ExtensionStatusCountMismatch {
+                stems: state_diff.len(),
+                statuses: statuses.len(),
+            });
+        }
+
+        // 1. Find leaves that are present and empty nodes
+        let mut leaves = BTreeMap::<Vec<u8>, (Stem, bool, bool)>::new();
+        let mut empty = BTreeSet::new();
+        let mut absent_other = vec![];
+        for (stem_state_diff, (depth, status)) in state_diff.iter().zip(statuses) {
+            let stem = stem_state_diff.stem;
+            let path = stem[..depth].to_vec();
+            if status != ExtensionStatus::Present {
+                for suffix_diff in stem_state_diff.suffix_diffs.iter() {
+                    if suffix_diff.current_value.is_some() {
+                        return Err(WitnessVerificationError::UnexpectedCurrentValue(
+                            TrieKey::from_stem_and_suffix(&stem, suffix_diff.suffix),
+                        ));
+                    }
+                }
+            }
+            match status {
+                ExtensionStatus::AbsentEmpty => {
+                    empty.insert(path);
+                }
+                ExtensionStatus::AbsentOther => absent_other.push(path),
+                ExtensionStatus::Present => {
+                    let has_c1 = stem_state_diff
+                        .suffix_diffs
+                        .iter()
+                        .any(|suffix_diff| suffix_diff.suffix < (VERKLE_NODE_WIDTH / 2) as u8);
+                    let has_c2 = stem_state_diff
+                        .suffix_diffs
+                        .iter()
+                        .any(|suffix_diff| suffix_diff.suffix >= (VERKLE_NODE_WIDTH / 2) as u8);
+                    if leaves
+                        .insert(path.clone(), (stem, has_c1, has_c2))
+                        .is_some()
+                    {
+                        return Err(WitnessVerificationError::InconsistentTrieStructure(
+                            path.into(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        // 2. Find leaves that are used as proof of absence for other stems.
+        // Multiple stems can be proven absent by the same leaf, in which case its stem is provided
+        // only once. If the leaf is also present, its stem is not provided at all.
+        let mut other_stems = verkle_proof.other_stems.iter();
+        for path in absent_other {
+            if leaves.contains_key(&path) {
+                continue;
+            }
+            let stem = other_stems
+                .next()
+                .ok_or(WitnessVerificationError::MissingProofOfAbsenceStem)?;
+            leaves.insert(path, (*stem, false, false));
+        }
+        if other_stems.len() > 0 {
+            return Err(WitnessVerificationError::UnusedProofOfAbsenceStems(
+                other_stems.len(),
+            ));
+        }
+
+        // 3. Find branch nodes (every prefix of the leaf or empty node path)
+        let mut branches = BTreeSet::new();
+        for path in leaves.keys().chain(&empty) {
+            for depth in 1..path.len() {
+                branches.insert(path[..depth].to_vec());
+            }
+        }
+        if let Some(path) = leaves
+            .keys()
+            .chain(&empty)
+            .find(|path| branches.contains(*path))
+            .or_else(|| leaves.keys().find(|path| empty.contains(*path)))
+        {
+            return Err(WitnessVerificationError::InconsistentTrieStructure(
+                Bytes::copy_from_slice(path),
+            ));
+        }
+
+        // 4. Assign commitments, ordered by path
+        let mut targets = BTreeMap::new();
+        for path in branches {
+            targets.insert(path, CommitmentTarget::Branch);
+        }
+        for (path, (_, has_c1, has_c2)) in &leaves {
+            targets.insert(path.clone(), CommitmentTarget::Leaf);
+            if *has_c1 {
+                targets.insert(
+                    [path.as_slice(), &[LEAF_C1_INDEX]].concat(),
+                    CommitmentTarget::C1,
+                );
+            }
+            if *has_c2 {
+                targets.insert(
+                    [path.as_slice(), &[LEAF_C2_INDEX]].concat(),
+                    CommitmentTarget::C2,
+                );
+            }
+        }
+        if targets.len() != verkle_proof.commitments_by_path.len() {
+            return Err(WitnessVerificationError::CommitmentsCountMismatch {
+                expected: targets.len(),
+                actual: verkle_proof.commitments_by_path.len(),
+            });
+        }
+
+        let mut trie = Self {
+            root,
+            branches: BTreeMap::new(),
+            leaves: BTreeMap::new(),
+            empty,
+        };
+        let mut last_leaf_path = vec![];
+        for ((path, target), commitment) in
+            targets.into_iter().zip(&verkle_proof.commitments_by_path)
+        {
+            let commitment = commitment.clone();
+            match target {
+                CommitmentTarget::Branch => {
+                    trie.branches.insert(path, commitment);
+                }
+                CommitmentTarget::Leaf => {
+                    let (stem, _, _) = leaves[&path];
+                    trie.leaves.insert(
+                        path.clone(),
+                        ReconstructedLeaf {
+                            stem,
+                            commitment,
+                            c1: None,
+                            c2: None,
+                        },
+                    );
+                    last_leaf_path = path;
+                }
+                // Suffix commitments always come right after their leaf
+                CommitmentTarget::C1 => trie.leaf_mut(&last_leaf_path).c1 = Some(commitment),
+                CommitmentTarget::C2 => trie.leaf_mut(&last_leaf_path).c2 = Some(commitment),
+            }
+        }
+        Ok(trie)
+    }
+
+    fn leaf_mut(&mut self, path: &[u8]) -> &mut ReconstructedLeaf {
+        self.leaves
+            .get_mut(path)
+            .expect("leaf should be present before its suffix commitments")
+    }
+
+    fn child_value(&self, path: &[u8]) -> Result<ScalarField, WitnessVerificationError> {
+        if let Some(commitment) = self.branches.get(path) {
+            Ok(commitment.map_to_scalar_field())
+        } else if let Some(leaf) = self.leaves.get(path) {
+            Ok(leaf.commitment.map_to_scalar_field())
+        } else if self.empty.contains(path) {
+            Ok(ScalarField::zero())
+        } else {
+            Err(WitnessVerificationError::InconsistentTrieStructure(
+                Bytes::copy_from_slice(path),
+            ))
+        }
+    }
+
+    /// Creates the multiquery that proves the current values of the provided keys.
+    ///
+    /// The keys have to be sorted. The order of queries is the same as in go-verkle: each branch
+    /// node first opens all of its accessed children, and only then the children are traversed.
+    /// Repeated openings of the same commitment at the same index are skipped.
+    pub fn verifier_multiquery(
+        &self,
+        keys: &[(TrieKey, Option<TrieValue>)],
+    ) -> Result<VerifierMultiQuery, WitnessVerificationError> {
+        let mut queries = QueriesBuilder::default();
+        self.add_branch_queries(&[], &self.root, keys, &mut queries)?;
+        Ok(queries.multiquery)
+    }
+
+    fn add_branch_queries(
+        &self,
+        path: &[u8],
+        commitment: &Point,
+        keys: &[(TrieKey, Option<TrieValue>)],
+        queries: &mut QueriesBuilder,
+    ) -> Result<(), WitnessVerificationError> {
+        let depth = path.len();
+        let groups = keys
+            .chunk_by(|(a, _), (b, _)| a[depth] == b[depth])
+            .collect::<Vec<_>>();
+
+        for group in &groups {
+            let child_index = group[0].0[depth];
+            let child_path = [path, &[child_index]].concat();
+            queries.add(commitment, child_index, self.child_value(&child_path)?);
+        }
+
+        for group in groups {
+            let child_path = [path, &[group[0].0[depth]]].concat();
+            if let Some(child_commitment) = self.branches.get(&child_path) {
+                self.add_branch_queries(&child_path, child_commitment, group, queries)?;
+            } else if let Some(leaf) = self.leaves.get(&child_path) {
+                Self::add_leaf_queries(leaf, group, queries)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_leaf_queries(
+        leaf: &ReconstructedLeaf,
+        keys: &[(TrieKey, Option<TrieValue>)],
+        queries: &mut QueriesBuilder,
+    ) -> Result<(), WitnessVerificationError> {
+        let commitment = &leaf.commitment;
+        queries.add(commitment, LEAF_MARKER_INDEX, ScalarField::one());
+        queries.add(commitment, LEAF_STEM_INDEX, ScalarField::from(&leaf.stem));
+
+        let present_keys = keys
+            .iter()
+            .filter(|(key, _)| key.starts_with_stem(&leaf.stem))
+            .collect::<Vec<_>>();
+        if present_keys.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(c1) = &leaf.c1 {
+            queries.add(commitment, LEAF_C1_INDEX, c1.map_to_scalar_field());
+        }
+        if let Some(c2) = &leaf.c2 {
+            queries.add(commitment, LEAF_C2_INDEX, c2.map_to_scalar_field());
+        }
+
+        for (key, value) in present_keys {
+            let suffix = key.suffix();
+            let suffix_commitment = if suffix < (VERKLE_NODE_WIDTH / 2) as u8 {
+                &leaf.c1
+            } else {
+                &leaf.c2
+            };
+            let suffix_commitment = suffix_commitment.as_ref().ok_or_else(|| {
+                WitnessVerificationError::InconsistentTrieStructure(Bytes::copy_from_slice(
+                    key.as_slice(),
+                ))
+            })?;
+            let low_index = 2 * (suffix % (VERKLE_NODE_WIDTH / 2) as u8);
+            let (low_value, high_value) = value.split();
+            queries.add(suffix_commitment, low_index, low_value);
+            queries.add(suffix_commitment, low_index + 1, high_value);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct QueriesBuilder {
+    multiquery: VerifierMultiQuery,
+    openings: HashSet<(B256, u8)>,
+}
+
+impl QueriesBuilder {
+    fn add(&mut self, commitment: &Point, z: u8, y: ScalarField) {
+        if self.openings.insert((B256::from(commitment), z)) {
+            self.multiquery.push(VerifierQuery {
+                commitment: commitment.clone(),
+                z,
+                y,
+            });
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::{typenum, VariableList};
+
+use crate::{Stem, TrieValue};
+
+pub type StateDiff = VariableList<StemStateDiff, typenum::U65536>;
+
+pub type SuffixStateDiffs = VariableList<SuffixStateDiff, typenum::U256>;
+
+/// The state diff of all accessed keys that share the same stem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct StemStateDiff {
+    pub stem: Stem,
+    pub suffix_diffs: SuffixStateDiffs,
+}
+
+/// The state diff of a single accessed key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SuffixStateDiff {
+    pub suffix: u8,
+    /// The value before the block was executed (`None` if key wasn't present).
+    pub current_value: Option<TrieValue>,
+    /// The value after the block was executed (`None` if key wasn't written).
+    pub new_value: Option<TrieValue>,
+}
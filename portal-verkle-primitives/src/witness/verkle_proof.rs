@@ -0,0 +1,134 @@
+use alloy_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use ssz_types::{typenum, VariableList};
+
+use crate::{
+    proof::{IpaProof, MultiProof},
+    Point, Stem,
+};
+
+use super::WitnessVerificationError;
+
+pub type OtherStems = VariableList<Stem, typenum::U65536>;
+
+/// At most 33 commitments per stem: 31 branch nodes, leaf node and its C1 or C2.
+pub type CommitmentsByPath = VariableList<Point, typenum::Prod<typenum::U65536, typenum::U33>>;
+
+/// The proof of the execution witness, in the format used by go-verkle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct VerkleProof {
+    /// The stems of the leaf nodes that are used to prove absence of other stems.
+    pub other_stems: OtherStems,
+    /// The depth and the extension status of each stem from the state diff.
+    ///
+    /// See [ExtensionStatus] for details.
+    pub depth_extension_present: Bytes,
+    /// The commitments of all nodes in the proof (except root), sorted by their path.
+    pub commitments_by_path: CommitmentsByPath,
+    /// The commitment to the polynomial g(x): `D=[g(x)]`
+    pub d: Point,
+    #[serde(with = "go_verkle_ipa_proof")]
+    pub ipa_proof: IpaProof,
+}
+
+/// Serializes [IpaProof] with camelCase field names, as go-verkle does.
+mod go_verkle_ipa_proof {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::{proof::IpaProof, Point, ScalarField};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    struct GoVerkleIpaProof {
+        cl: Vec<Point>,
+        cr: Vec<Point>,
+        final_evaluation: ScalarField,
+    }
+
+    pub fn serialize<S: Serializer>(proof: &IpaProof, serializer: S) -> Result<S::Ok, S::Error> {
+        GoVerkleIpaProof {
+            cl: proof.cl.clone(),
+            cr: proof.cr.clone(),
+            final_evaluation: proof.final_evaluation.clone(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<IpaProof, D::Error> {
+        let proof = GoVerkleIpaProof::deserialize(deserializer)?;
+        Ok(IpaProof {
+            cl: proof.cl,
+            cr: proof.cr,
+            final_evaluation: proof.final_evaluation,
+        })
+    }
+}
+
+impl VerkleProof {
+    pub fn multiproof(&self) -> MultiProof {
+        MultiProof {
+            ipa_proof: self.ipa_proof.clone(),
+            g_commitment: self.d.clone(),
+        }
+    }
+
+    /// Returns the depth and the extension status for each stem.
+    pub fn depth_extension_statuses(
+        &self,
+    ) -> Result<Vec<(usize, ExtensionStatus)>, WitnessVerificationError> {
+        self.depth_extension_present
+            .iter()
+            .map(|depth_extension| ExtensionStatus::decode(*depth_extension))
+            .collect()
+    }
+}
+
+/// The extension status of the stem, describing what is found at the end of its path.
+///
+/// It's encoded together with the depth at which it was found: `status | (depth << 3)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtensionStatus {
+    /// The path ends with an empty node.
+    AbsentEmpty,
+    /// The path ends with a leaf node that has a different stem.
+    AbsentOther,
+    /// The path ends with a leaf node that has the same stem.
+    Present,
+}
+
+impl ExtensionStatus {
+    const ABSENT_EMPTY: u8 = 0;
+    const ABSENT_OTHER: u8 = 1;
+    const PRESENT: u8 = 2;
+
+    pub fn encode(self, depth: usize) -> u8 {
+        let status = match self {
+            Self::AbsentEmpty => Self::ABSENT_EMPTY,
+            Self::AbsentOther => Self::ABSENT_OTHER,
+            Self::Present => Self::PRESENT,
+        };
+        status | ((depth as u8) << 3)
+    }
+
+    pub fn decode(depth_extension: u8) -> Result<(usize, Self), WitnessVerificationError> {
+        let depth = (depth_extension >> 3) as usize;
+        let status = match depth_extension & 0b111 {
+            Self::ABSENT_EMPTY => Self::AbsentEmpty,
+            Self::ABSENT_OTHER => Self::AbsentOther,
+            Self::PRESENT => Self::Present,
+            _ => {
+                return Err(WitnessVerificationError::InvalidExtensionStatus(
+                    depth_extension,
+                ))
+            }
+        };
+        if depth == 0 || depth > Stem::len_bytes() {
+            return Err(WitnessVerificationError::InvalidExtensionStatus(
+                depth_extension,
+            ));
+        }
+        Ok((depth, status))
+    }
+}
@@ -3,6 +3,7 @@ use std::{collections::HashSet, fs::File, io::BufReader};
 use alloy_primitives::B256;
 use portal_verkle_primitives::{
     constants::PORTAL_NETWORK_NODE_WIDTH,
+    portal::NodeVerificationError,
     verkle::{
         genesis_config::GenesisConfig,
         nodes::{
@@ -23,7 +24,7 @@ fn leaves() {
     let state_writes = read_genesis().into_state_writes();
 
     let mut trie = VerkleTrie::new();
-    trie.update(&state_writes);
+    trie.update(&state_writes).unwrap();
 
     for state_write in state_writes.iter() {
         let stem = state_write.stem;
@@ -41,7 +42,9 @@ fn check_leaf(path_to_leaf: &PathToLeaf) {
         "Leaf bundle {:?}",
         path_to_leaf.leaf.commitment().as_point()
     );
-    let bundle_node = leaf_node_builder.bundle_node_with_proof(GenesisConfig::DEVNET6_BLOCK_HASH);
+    let bundle_node = leaf_node_builder
+        .bundle_node_with_proof(GenesisConfig::DEVNET6_BLOCK_HASH)
+        .unwrap();
     let verification_result = bundle_node.verify(
         path_to_leaf.leaf.commitment().as_point(),
         &GenesisConfig::DEVNET6_STATE_ROOT,
@@ -59,7 +62,8 @@ fn check_leaf(path_to_leaf: &PathToLeaf) {
 
         println!("   fragment {:?}", commitment);
         let fragment_node = leaf_node_builder
-            .fragment_node_with_proof(fragment_index, GenesisConfig::DEVNET6_BLOCK_HASH);
+            .fragment_node_with_proof(fragment_index, GenesisConfig::DEVNET6_BLOCK_HASH)
+            .unwrap();
         let verification_result = fragment_node.verify(
             commitment,
             &GenesisConfig::DEVNET6_STATE_ROOT,
@@ -74,7 +78,7 @@ fn branches() {
     let state_writes = read_genesis().into_state_writes();
 
     let mut trie = VerkleTrie::new();
-    trie.update(&state_writes);
+    trie.update(&state_writes).unwrap();
 
     let mut checked_branches = HashSet::new();
 
@@ -101,7 +105,9 @@ fn check_branch(branch_node: &BranchNode, trie_path: &[(&BranchNode, u8)]) {
     let branch_node_builder = PortalBranchNodeBuilder::new(branch_node, trie_path).unwrap();
 
     println!("Branch bundle {:?}", branch_node.commitment().as_point());
-    let bundle_node = branch_node_builder.bundle_node_with_proof(GenesisConfig::DEVNET6_BLOCK_HASH);
+    let bundle_node = branch_node_builder
+        .bundle_node_with_proof(GenesisConfig::DEVNET6_BLOCK_HASH)
+        .unwrap();
     let verification_result = bundle_node.verify(
         branch_node.commitment().as_point(),
         &GenesisConfig::DEVNET6_STATE_ROOT,
@@ -116,9 +122,70 @@ fn check_branch(branch_node: &BranchNode, trie_path: &[(&BranchNode, u8)]) {
 
         println!("     fragment {fragment_index} {:?}", commitment);
         let fragment_node = branch_node_builder
-            .fragment_node_with_proof(fragment_index, GenesisConfig::DEVNET6_BLOCK_HASH);
+            .fragment_node_with_proof(fragment_index, GenesisConfig::DEVNET6_BLOCK_HASH)
+            .unwrap();
         let verification_result =
             fragment_node.verify(commitment, &GenesisConfig::DEVNET6_STATE_ROOT);
         assert!(matches!(verification_result, Ok(())))
     }
 }
+
+#[test]
+fn verify_with_parent() {
+    let state_writes = read_genesis().into_state_writes();
+
+    let mut trie = VerkleTrie::new();
+    trie.update(&state_writes).unwrap();
+
+    for state_write in state_writes.iter().take(5) {
+        let path_to_leaf = trie.traverse_to_leaf(&state_write.stem).unwrap();
+
+        let mut parent_fragment = None;
+        for (depth, (branch_node, child_index)) in path_to_leaf.trie_path.iter().enumerate() {
+            let builder =
+                PortalBranchNodeBuilder::new(branch_node, &path_to_leaf.trie_path[..depth])
+                    .unwrap();
+            let bundle_node = builder.bundle_node().unwrap();
+            match &parent_fragment {
+                None => bundle_node
+                    .verify(branch_node.commitment().as_point())
+                    .unwrap(),
+                Some((parent_fragment, parent_child_index)) => bundle_node
+                    .verify_with_parent(parent_fragment, *parent_child_index)
+                    .unwrap(),
+            }
+
+            let fragment_node =
+                builder.fragment_node(*child_index / PORTAL_NETWORK_NODE_WIDTH as u8);
+            fragment_node.verify_with_parent(&bundle_node).unwrap();
+            parent_fragment = Some((fragment_node, *child_index));
+        }
+
+        let (parent_fragment, parent_child_index) = parent_fragment.unwrap();
+        let leaf_node_builder = PortalLeafNodeBuilder::new(&path_to_leaf);
+        let bundle_node = leaf_node_builder.bundle_node().unwrap();
+        bundle_node
+            .verify_with_parent(&parent_fragment, parent_child_index)
+            .unwrap();
+        assert!(matches!(
+            bundle_node.verify_with_parent(&parent_fragment, parent_child_index ^ 1),
+            Err(NodeVerificationError::MissingChild(_)
+                | NodeVerificationError::WrongCommitment { .. })
+        ));
+
+        for fragment_index in 0..PORTAL_NETWORK_NODE_WIDTH as u8 {
+            let fragment_node = leaf_node_builder.fragment_node(fragment_index);
+            if leaf_node_builder
+                .fragment_commitment(fragment_index)
+                .is_zero()
+            {
+                assert!(matches!(
+                    fragment_node.verify_with_parent(&bundle_node),
+                    Err(NodeVerificationError::MissingChild(_))
+                ));
+            } else {
+                fragment_node.verify_with_parent(&bundle_node).unwrap();
+            }
+        }
+    }
+}